@@ -0,0 +1,539 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    bracketed, parse::Parse, parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput,
+    Error, Expr, ExprLit, Field, GenericArgument, Index, Lit, LitStr, Member, Path, PathArguments,
+    Token, Type,
+};
+
+/// Implements `VertexData`. The scalar type is given by `#[vertex(scalar = T)]`
+/// on the struct, or else taken from the `Vect` of the `#[vertex(position)]`
+/// field.
+///
+/// Without either, there's no scalar to use:
+///
+/// ```compile_fail
+/// use mish_mesh::{ear_algae::Vect, vertex::VertexData};
+///
+/// #[derive(Copy, Clone, VertexData)]
+/// struct Vert {
+///     pos: Vect<3, f32>,
+/// }
+/// ```
+#[proc_macro_derive(VertexData, attributes(vertex))]
+pub fn derive_vertex_data(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    vertex_data(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `VertexPosition` over the `#[vertex(position)]` field.
+#[proc_macro_derive(VertexPosition, attributes(vertex))]
+pub fn derive_vertex_position(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    vertex_accessor(&input, Role::Position)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `VertexNormal` over the `#[vertex(normal)]` field.
+#[proc_macro_derive(VertexNormal, attributes(vertex))]
+pub fn derive_vertex_normal(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    vertex_accessor(&input, Role::Normal)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `PlyVertex`, with one PLY property per scalar field and one per
+/// component of each `Vect` or array field.
+///
+/// Properties are named after their field, except that the position defaults
+/// to `x`, `y`, `z` and the normal to `nx`, `ny`, `nz`. Use
+/// `#[ply(name = "...")]` on a scalar field or `#[ply(names = [...])]` on a
+/// vector field to rename them, and `#[ply(skip)]` to leave a field out and
+/// read it back as its `Default`.
+///
+/// ```
+/// use mish_mesh::{
+///     ear_algae::Vect,
+///     ply::PlyVertex,
+///     vertex::{VertexData, VertexPosition},
+/// };
+///
+/// #[derive(Copy, Clone, VertexData, VertexPosition, PlyVertex)]
+/// struct Vert {
+///     #[vertex(position)]
+///     pos: Vect<3, f32>,
+///     #[ply(name = "quality")]
+///     weight: f32,
+///     #[ply(skip)]
+///     selected: bool,
+/// }
+///
+/// // Tuple fields have no name to fall back on, so vectors and arrays other
+/// // than the position and normal need theirs spelled out.
+/// #[derive(Copy, Clone, VertexData, VertexPosition, PlyVertex)]
+/// struct Point(
+///     #[vertex(position)] Vect<3, f64>,
+///     #[ply(names = ["s", "t"])] [f32; 2],
+/// );
+/// ```
+///
+/// There must be exactly one name for each property:
+///
+/// ```compile_fail
+/// # use mish_mesh::{
+/// #     ear_algae::Vect,
+/// #     ply::PlyVertex,
+/// #     vertex::{VertexData, VertexPosition},
+/// # };
+/// #[derive(Copy, Clone, VertexData, VertexPosition, PlyVertex)]
+/// struct Vert {
+///     #[vertex(position)]
+///     #[ply(names = ["x", "y"])]
+///     pos: Vect<3, f32>,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use mish_mesh::{
+/// #     ear_algae::Vect,
+/// #     ply::PlyVertex,
+/// #     vertex::{VertexData, VertexPosition},
+/// # };
+/// #[derive(Copy, Clone, VertexData, VertexPosition, PlyVertex)]
+/// struct Vert {
+///     #[vertex(position)]
+///     pos: Vect<3, f32>,
+///     #[ply(names = ["quality", "confidence"])]
+///     weight: f32,
+/// }
+/// ```
+#[proc_macro_derive(PlyVertex, attributes(ply, vertex))]
+pub fn derive_ply_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    ply_vertex(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Role {
+    Position,
+    Normal,
+}
+
+impl Role {
+    fn flag(self) -> &'static str {
+        match self {
+            Role::Position => "position",
+            Role::Normal => "normal",
+        }
+    }
+}
+
+struct VertexField<'a> {
+    member: Member,
+    field: &'a Field,
+    role: Option<Role>,
+}
+
+fn vertex_fields(input: &DeriveInput) -> syn::Result<Vec<VertexField<'_>>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "vertex traits can only be derived for structs",
+        ));
+    };
+    data.fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(i)),
+            };
+            let mut role = None;
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("vertex")) {
+                attr.parse_nested_meta(|meta| {
+                    let found = [Role::Position, Role::Normal]
+                        .into_iter()
+                        .find(|r| meta.path.is_ident(r.flag()))
+                        .ok_or_else(|| meta.error("expected `position` or `normal`"))?;
+                    if role.replace(found).is_some() {
+                        return Err(meta.error("a field can only have one vertex role"));
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(VertexField {
+                member,
+                field,
+                role,
+            })
+        })
+        .collect()
+}
+
+fn role_field<'a, 'f>(
+    input: &DeriveInput,
+    fields: &'a [VertexField<'f>],
+    role: Role,
+) -> syn::Result<&'a VertexField<'f>> {
+    let mut matching = fields.iter().filter(|f| f.role == Some(role));
+    let Some(field) = matching.next() else {
+        return Err(Error::new(
+            input.ident.span(),
+            format!("expected a field marked `#[vertex({})]`", role.flag()),
+        ));
+    };
+    if let Some(other) = matching.next() {
+        return Err(Error::new(
+            other.field.span(),
+            format!("only one field can be marked `#[vertex({})]`", role.flag()),
+        ));
+    }
+    Ok(field)
+}
+
+/// The last segment of a type path, such as `Vect` in `ear_algae::Vect<3, S>`.
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn generic_args(ty: &Type) -> Vec<&GenericArgument> {
+    match last_segment(ty).map(|s| &s.arguments) {
+        Some(PathArguments::AngleBracketed(args)) => args.args.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn vertex_data(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut scalar = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("vertex")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("scalar") {
+                scalar = Some(meta.value()?.parse::<Type>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `scalar = ...`"))
+            }
+        })?;
+    }
+    let scalar = match scalar {
+        Some(scalar) => scalar,
+        None => {
+            let fields = vertex_fields(input)?;
+            let position = role_field(input, &fields, Role::Position)?;
+            let ty = &position.field.ty;
+            match generic_args(ty).last() {
+                Some(GenericArgument::Type(scalar)) => scalar.clone(),
+                _ => {
+                    return Err(Error::new(
+                        ty.span(),
+                        "can't find the scalar type here; add `#[vertex(scalar = ...)]` to the struct",
+                    ));
+                }
+            }
+        }
+    };
+
+    // `VertexData` needs `Copy`, which the derived `Copy` only gives when every
+    // type parameter is `Copy`, not just the scalar.
+    let mut generics = input.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.push(parse_quote!(#scalar: ::mish_mesh::ear_algae::traits::Field));
+    predicates.push(parse_quote!(Self: ::core::marker::Copy));
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::mish_mesh::vertex::VertexData for #name #ty_generics #where_clause {
+            type Scalar = #scalar;
+        }
+    })
+}
+
+fn vertex_accessor(input: &DeriveInput, role: Role) -> syn::Result<TokenStream2> {
+    let fields = vertex_fields(input)?;
+    let field = role_field(input, &fields, role)?;
+    let (member, ty) = (&field.member, &field.field.ty);
+    let (trait_name, get, set) = match role {
+        Role::Position => (quote!(VertexPosition), quote!(pos), quote!(set_pos)),
+        Role::Normal => (quote!(VertexNormal), quote!(normal), quote!(set_normal)),
+    };
+
+    // Bound the type as the `VertexData` impl does, so that it applies here.
+    let mut generics = input.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    if let Some(GenericArgument::Type(scalar)) = generic_args(ty).last() {
+        predicates.push(parse_quote!(#scalar: ::mish_mesh::ear_algae::traits::Field));
+    }
+    predicates.push(parse_quote!(Self: ::core::marker::Copy));
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::mish_mesh::vertex::#trait_name for #name #ty_generics #where_clause {
+            fn #get(self) -> #ty {
+                self.#member
+            }
+
+            fn #set(&mut self, value: #ty) {
+                self.#member = value
+            }
+        }
+    })
+}
+
+/// How a field is laid out as PLY properties.
+enum Shape {
+    Scalar,
+    Array,
+    /// A tuple struct wrapping an array, such as `Vect`, built through `Path`.
+    Wrapped(Path),
+}
+
+struct PlyField<'a> {
+    member: Member,
+    shape: Shape,
+    /// The type of each property.
+    element: Type,
+    names: Vec<LitStr>,
+    skip: bool,
+    field: &'a Field,
+}
+
+fn ply_field<'a>(field: &VertexField<'a>) -> syn::Result<PlyField<'a>> {
+    let mut names = None;
+    let mut names_span = None;
+    let mut skip = false;
+    for attr in field
+        .field
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("ply"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+            } else if meta.path.is_ident("name") {
+                names_span = Some(meta.path.span());
+                names = Some(vec![meta.value()?.parse::<LitStr>()?]);
+            } else if meta.path.is_ident("names") {
+                names_span = Some(meta.path.span());
+                let value = meta.value()?;
+                let content;
+                bracketed!(content in value);
+                let list = content.parse_terminated(<LitStr as Parse>::parse, Token![,])?;
+                names = Some(list.into_iter().collect());
+            } else {
+                return Err(meta.error("expected `name`, `names` or `skip`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let ty = &field.field.ty;
+    let (shape, element, len) = match ty {
+        Type::Array(array) => (Shape::Array, (*array.elem).clone(), Some(&array.len)),
+        _ if last_segment(ty).is_some_and(|s| s.ident == "Vect") => {
+            let args = generic_args(ty);
+            let (Some(GenericArgument::Const(len)), Some(GenericArgument::Type(element))) =
+                (args.first(), args.get(1))
+            else {
+                return Err(Error::new(ty.span(), "expected `Vect<N, S>`"));
+            };
+            let Type::Path(path) = ty else { unreachable!() };
+            let mut path = path.path.clone();
+            if let Some(segment) = path.segments.last_mut() {
+                segment.arguments = PathArguments::None;
+            }
+            (Shape::Wrapped(path), element.clone(), Some(len))
+        }
+        _ => (Shape::Scalar, ty.clone(), None),
+    };
+    // The number of properties, if it can be read off the type.
+    let fixed_len = match (&shape, len) {
+        (Shape::Scalar, _) => Some(1),
+        (
+            _,
+            Some(Expr::Lit(ExprLit {
+                lit: Lit::Int(len), ..
+            })),
+        ) => Some(len.base10_parse::<usize>()?),
+        _ => None,
+    };
+    if let (Some(names), Some(span), Some(len)) = (&names, names_span, fixed_len)
+        && names.len() != len
+    {
+        let (properties, hint) = if len == 1 {
+            ("property", "use `#[ply(name = \"...\")]`")
+        } else {
+            (
+                "properties",
+                "use `#[ply(names = [...])]` with a name for each component",
+            )
+        };
+        return Err(Error::new(
+            span,
+            format!(
+                "this field has {len} PLY {properties} but {} names; {hint}",
+                names.len()
+            ),
+        ));
+    }
+
+    let base = match &field.member {
+        Member::Named(ident) => Some(ident.to_string()),
+        Member::Unnamed(_) => None,
+    };
+    let names = match (names, len) {
+        (Some(names), _) => names,
+        (None, None) => match base {
+            Some(base) => vec![LitStr::new(&base, ty.span())],
+            None => return Err(Error::new(ty.span(), "add `#[ply(name = \"...\")]`")),
+        },
+        (None, Some(_)) => {
+            let defaults: &[&str] = match field.role {
+                Some(Role::Position) => &["x", "y", "z"],
+                Some(Role::Normal) => &["nx", "ny", "nz"],
+                None => &[],
+            };
+            let len = fixed_len.unwrap_or(defaults.len());
+            if defaults.len() == len {
+                defaults.iter().map(|n| LitStr::new(n, ty.span())).collect()
+            } else {
+                match (base, len) {
+                    (Some(base), 1..) => (0..len)
+                        .map(|i| LitStr::new(&format!("{base}_{i}"), ty.span()))
+                        .collect(),
+                    _ => {
+                        return Err(Error::new(
+                            ty.span(),
+                            "add `#[ply(names = [...])]` with a name for each component",
+                        ));
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(PlyField {
+        member: field.member.clone(),
+        shape,
+        element,
+        names,
+        skip,
+        field: field.field,
+    })
+}
+
+fn ply_vertex(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = vertex_fields(input)?
+        .iter()
+        .map(ply_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let ply = quote!(::mish_mesh::plyers::ply);
+    let property = quote!(::mish_mesh::ply::properties::PlyPropery);
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in fields.iter().filter(|f| !f.skip) {
+        let element = &field.element;
+        where_clause
+            .predicates
+            .push(parse_quote!(#element: #property + ::core::marker::Copy));
+    }
+    for field in fields.iter().filter(|f| f.skip) {
+        let ty = &field.field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::core::default::Default));
+    }
+
+    let mut defs = Vec::new();
+    let mut sets = Vec::new();
+    let mut gets = Vec::new();
+    for field in &fields {
+        let PlyField {
+            member,
+            shape,
+            element,
+            names,
+            skip,
+            ..
+        } = field;
+        if *skip {
+            gets.push(quote!(#member: ::core::default::Default::default()));
+            continue;
+        }
+
+        defs.extend(names.iter().map(|name| {
+            quote! {
+                def.properties.add(#ply::PropertyDef::new(
+                    ::std::borrow::ToOwned::to_owned(#name),
+                    <#element as #property>::PLY_PROP_TYPE,
+                ));
+            }
+        }));
+
+        let values = match shape {
+            Shape::Scalar => vec![quote!(self.#member)],
+            Shape::Array | Shape::Wrapped(_) => {
+                (0..names.len()).map(|i| quote!(self.#member[#i])).collect()
+            }
+        };
+        sets.extend(names.iter().zip(values).map(|(name, value)| {
+            quote! {
+                vert.set_property(
+                    ::std::borrow::ToOwned::to_owned(#name),
+                    <#element as #property>::into_prop(#value),
+                );
+            }
+        }));
+
+        let reads = names
+            .iter()
+            .map(|name| quote!(<#element as #property>::from_element_prop(element, #name)?))
+            .collect::<Vec<_>>();
+        gets.push(match shape {
+            Shape::Scalar => quote!(#member: #(#reads)*),
+            Shape::Array => quote!(#member: [#(#reads),*]),
+            Shape::Wrapped(path) => quote!(#member: #path([#(#reads),*])),
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = &generics.where_clause;
+    Ok(quote! {
+        impl #impl_generics ::mish_mesh::ply::PlyVertex for #name #ty_generics #where_clause {
+            fn element_def(name: ::std::string::String) -> #ply::ElementDef {
+                let mut def = #ply::ElementDef::new(name);
+                #(#defs)*
+                def
+            }
+
+            fn into_ply_element<__E: #ply::PlyElementSet>(self) -> __E {
+                let mut vert = <__E as #ply::PlyElementSet>::new();
+                #(#sets)*
+                vert
+            }
+
+            fn from_ply_element<__E: #ply::PlyElementGet>(
+                element: &__E,
+            ) -> ::core::option::Option<Self> {
+                ::core::option::Option::Some(Self {
+                    #(#gets,)*
+                })
+            }
+        }
+    })
+}
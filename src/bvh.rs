@@ -0,0 +1,340 @@
+use std::cmp::Ordering;
+
+use ear_algae::{
+    prelude::*,
+    traits::{Field, Ring},
+};
+
+use crate::{vertex::VertexPosition, Mesh};
+
+const LEAF_SIZE: usize = 4;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Aabb<S: Field> {
+    pub min: Vect<3, S>,
+    pub max: Vect<3, S>,
+}
+
+impl<S: Field + PartialOrd> Aabb<S> {
+    pub fn from_points(points: impl IntoIterator<Item = Vect<3, S>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(
+            Self {
+                min: first,
+                max: first,
+            },
+            |aabb, p| aabb.grow(p),
+        ))
+    }
+
+    pub fn grow(self, p: Vect<3, S>) -> Self {
+        let mut this = self;
+        for i in 0..3 {
+            if p[i] < this.min[i] {
+                this.min.0[i] = p[i];
+            }
+            if p[i] > this.max[i] {
+                this.max.0[i] = p[i];
+            }
+        }
+        this
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        self.grow(other.min).grow(other.max)
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    pub fn contains(&self, p: Vect<3, S>) -> bool {
+        (0..3).all(|i| self.min[i] <= p[i] && p[i] <= self.max[i])
+    }
+
+    pub fn center(&self) -> Vect<3, S> {
+        (self.min + self.max) * (S::ONE / (S::ONE + S::ONE))
+    }
+
+    fn longest_axis(&self) -> usize {
+        let size = self.max - self.min;
+        if size[0] >= size[1] && size[0] >= size[2] {
+            0
+        } else if size[1] >= size[2] {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// The parametric interval over which `ray` lies inside the box, if any,
+    /// optionally cut off at `far`.
+    fn ray_interval(&self, ray: &Ray<S>, mut far: Option<S>) -> Option<(S, Option<S>)> {
+        let mut near = S::ZERO;
+        for i in 0..3 {
+            if ray.direction[i] == S::ZERO {
+                if ray.origin[i] < self.min[i] || ray.origin[i] > self.max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let inv = S::ONE / ray.direction[i];
+            let mut t0 = (self.min[i] - ray.origin[i]) * inv;
+            let mut t1 = (self.max[i] - ray.origin[i]) * inv;
+            if t0 > t1 {
+                (t0, t1) = (t1, t0);
+            }
+            if t0 > near {
+                near = t0;
+            }
+            if far.is_none_or(|far| t1 < far) {
+                far = Some(t1);
+            }
+            if far.is_some_and(|far| near > far) {
+                return None;
+            }
+        }
+        Some((near, far))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Ray<S: Field> {
+    pub origin: Vect<3, S>,
+    pub direction: Vect<3, S>,
+}
+
+impl<S: Field> Ray<S> {
+    pub fn new(origin: Vect<3, S>, direction: Vect<3, S>) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn at(&self, t: S) -> Vect<3, S> {
+        self.origin + self.direction * t
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RayHit<S: Field> {
+    pub tri: usize,
+    /// Distance along the ray, in multiples of its direction.
+    pub t: S,
+    /// Weights of the triangle's three corners at the hit point.
+    pub bary: [S; 3],
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum NodeKind {
+    Leaf { start: usize, end: usize },
+    Branch { left: usize, right: usize },
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Node<S: Field> {
+    pub(crate) bounds: Aabb<S>,
+    pub(crate) kind: NodeKind,
+}
+
+/// A bounding volume hierarchy over the triangles of a [`Mesh`].
+///
+/// Triangle positions are copied in, so the hierarchy does not borrow the mesh,
+/// but it goes stale if the mesh is edited.
+#[derive(Clone, Debug)]
+pub struct Bvh<S: Field> {
+    pub(crate) nodes: Vec<Node<S>>,
+    pub(crate) order: Vec<usize>,
+    pub(crate) tris: Vec<[Vect<3, S>; 3]>,
+}
+
+impl<S: Field + PartialOrd> Bvh<S> {
+    pub fn new<V: VertexPosition<Scalar = S>>(mesh: &Mesh<V>) -> Self {
+        let tris = mesh
+            .tris_iter()
+            .map(|t| t.map(|i| mesh.verts[i].pos()))
+            .collect::<Vec<_>>();
+        let mut this = Self {
+            nodes: Vec::new(),
+            order: (0..tris.len()).collect(),
+            tris,
+        };
+        if !this.tris.is_empty() {
+            this.build(0, this.tris.len());
+        }
+        this
+    }
+
+    fn tri_bounds(&self, t: usize) -> Aabb<S> {
+        Aabb::from_points(self.tris[t]).unwrap() // A triangle always has points.
+    }
+
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let bounds = self.order[start..end]
+            .iter()
+            .map(|&t| self.tri_bounds(t))
+            .reduce(Aabb::union)
+            .unwrap(); // Never called with an empty range.
+
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            bounds,
+            kind: NodeKind::Leaf { start, end },
+        });
+
+        if end - start > LEAF_SIZE {
+            let centers = Aabb::from_points(
+                self.order[start..end]
+                    .iter()
+                    .map(|&t| self.tri_bounds(t).center()),
+            )
+            .unwrap();
+            let axis = centers.longest_axis();
+
+            let tris = &self.tris;
+            let key = |&t: &usize| tris[t][0][axis] + tris[t][1][axis] + tris[t][2][axis];
+            self.order[start..end]
+                .sort_unstable_by(|a, b| key(a).partial_cmp(&key(b)).unwrap_or(Ordering::Equal));
+
+            let mid = (start + end) / 2;
+            let left = self.build(start, mid);
+            let right = self.build(mid, end);
+            self.nodes[index].kind = NodeKind::Branch { left, right };
+        }
+
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.tris.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tris.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<Aabb<S>> {
+        self.nodes.first().map(|n| n.bounds)
+    }
+
+    /// Visits every leaf triangle whose ancestors all pass `enter`.
+    pub(crate) fn visit(
+        &self,
+        mut enter: impl FnMut(&Aabb<S>) -> bool,
+        mut leaf: impl FnMut(usize),
+    ) {
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if !enter(&node.bounds) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf { start, end } => {
+                    self.order[start..end].iter().for_each(|&t| leaf(t))
+                }
+                NodeKind::Branch { left, right } => stack.extend([right, left]),
+            }
+        }
+    }
+
+    /// Möller–Trumbore intersection of `ray` with triangle `t`, limited to
+    /// non-negative distances.
+    pub fn intersect_tri(&self, ray: &Ray<S>, t: usize) -> Option<RayHit<S>> {
+        let [a, b, c] = self.tris[t];
+        let e1 = b - a;
+        let e2 = c - a;
+        let p = ray.direction.cross(e2);
+        let det = e1.dot(p);
+        if det == S::ZERO {
+            return None;
+        }
+        let inv = S::ONE / det;
+        let s = ray.origin - a;
+        let u = s.dot(p) * inv;
+        if u < S::ZERO || u > S::ONE {
+            return None;
+        }
+        let q = s.cross(e1);
+        let v = ray.direction.dot(q) * inv;
+        if v < S::ZERO || u + v > S::ONE {
+            return None;
+        }
+        let dist = e2.dot(q) * inv;
+        if dist < S::ZERO {
+            return None;
+        }
+        Some(RayHit {
+            tri: t,
+            t: dist,
+            bary: [S::ONE - u - v, u, v],
+        })
+    }
+
+    pub fn ray_first(&self, ray: &Ray<S>) -> Option<RayHit<S>> {
+        let mut best: Option<RayHit<S>> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if node
+                .bounds
+                .ray_interval(ray, best.map(|hit| hit.t))
+                .is_none()
+            {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf { start, end } => {
+                    for &t in &self.order[start..end] {
+                        if let Some(hit) = self.intersect_tri(ray, t) {
+                            if best.is_none_or(|b| hit.t < b.t) {
+                                best = Some(hit);
+                            }
+                        }
+                    }
+                }
+                NodeKind::Branch { left, right } => stack.extend([right, left]),
+            }
+        }
+        best
+    }
+
+    /// Every intersection of `ray` with the mesh, nearest first.
+    pub fn ray_all(&self, ray: &Ray<S>) -> Vec<RayHit<S>> {
+        let mut hits = Vec::new();
+        self.visit(
+            |bounds| bounds.ray_interval(ray, None).is_some(),
+            |t| hits.extend(self.intersect_tri(ray, t)),
+        );
+        hits.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap_or(Ordering::Equal));
+        hits
+    }
+
+    /// The triangles whose bounding boxes overlap `aabb`.
+    pub fn overlapping(&self, aabb: &Aabb<S>) -> Vec<usize> {
+        let mut tris = Vec::new();
+        self.visit(
+            |bounds| bounds.overlaps(aabb),
+            |t| {
+                if self.tri_bounds(t).overlaps(aabb) {
+                    tris.push(t)
+                }
+            },
+        );
+        tris
+    }
+}
+
+impl<V: VertexPosition> Mesh<V>
+where
+    V::Scalar: PartialOrd,
+{
+    pub fn bvh(&self) -> Bvh<V::Scalar> {
+        Bvh::new(self)
+    }
+}
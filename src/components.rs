@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use crate::{union_find::UnionFind, Mesh};
+
+/// What two triangles must share to be considered connected.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Connectivity {
+    Vertex,
+    #[default]
+    Edge,
+}
+
+impl<V: Copy> Mesh<V> {
+    /// Labels every triangle with its connected component. Labels are dense and
+    /// numbered in order of each component's first triangle.
+    pub fn component_labels(&self, connectivity: Connectivity) -> Vec<usize> {
+        let mut components = UnionFind::new(self.tris.len());
+
+        match connectivity {
+            Connectivity::Vertex => {
+                let mut first: HashMap<usize, usize> = HashMap::new();
+                for (t, tri) in self.tris.iter().enumerate() {
+                    for &v in tri {
+                        let f = *first.entry(v).or_insert(t);
+                        components.union(f, t);
+                    }
+                }
+            }
+            Connectivity::Edge => {
+                for tris in self.edge_tris().values() {
+                    for &t in &tris[1..] {
+                        components.union(tris[0], t);
+                    }
+                }
+            }
+        }
+
+        let mut labels: HashMap<usize, usize> = HashMap::new();
+        (0..self.tris.len())
+            .map(|t| {
+                let root = components.find(t);
+                let len = labels.len();
+                *labels.entry(root).or_insert(len)
+            })
+            .collect()
+    }
+
+    pub fn component_count(&self, connectivity: Connectivity) -> usize {
+        self.component_labels(connectivity)
+            .into_iter()
+            .max()
+            .map_or(0, |l| l + 1)
+    }
+
+    /// Splits the mesh into one mesh per connected component, in label order.
+    pub fn split_components(&self, connectivity: Connectivity) -> Vec<Mesh<V>> {
+        let labels = self.component_labels(connectivity);
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (t, &label) in labels.iter().enumerate() {
+            if label == groups.len() {
+                groups.push(Vec::new());
+            }
+            groups[label].push(t);
+        }
+        groups.into_iter().map(|tris| self.submesh(tris)).collect()
+    }
+
+    /// Drops vertices that no triangle refers to.
+    pub fn without_unused_verts(self) -> Self {
+        self.submesh(0..self.tris.len())
+    }
+
+    /// Builds a mesh from a subset of the triangles, keeping only the vertices
+    /// they use.
+    pub fn submesh(&self, tris: impl IntoIterator<Item = usize>) -> Mesh<V> {
+        let mut index_map = HashMap::<usize, usize>::new();
+        let mut verts = Vec::new();
+        let tris = tris
+            .into_iter()
+            .map(|t| {
+                self.tris[t].map(|i| {
+                    *index_map.entry(i).or_insert_with(|| {
+                        verts.push(self.verts[i]);
+                        verts.len() - 1
+                    })
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+
+        Mesh {
+            verts: verts.into_boxed_slice(),
+            tris,
+        }
+    }
+}
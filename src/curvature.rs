@@ -0,0 +1,200 @@
+use ear_algae::{
+    prelude::*,
+    traits::{Field, Ring},
+};
+
+use crate::{
+    scalar::{angle_between, int},
+    vertex::VertexPosition,
+    Mesh,
+};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Curvature<S: Field> {
+    /// Positive where the surface bends away from its normal, as on the outside
+    /// of a sphere.
+    pub mean: S,
+    pub gaussian: S,
+    /// The maximum and minimum normal curvatures.
+    pub principal: [S; 2],
+    /// Unit tangent directions of the two principal curvatures.
+    pub directions: [Vect<3, S>; 2],
+}
+
+fn det3<S: Field>(m: [[S; 3]; 3]) -> S {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+impl<V: VertexPosition> Mesh<V>
+where
+    V::Scalar: PartialOrd,
+{
+    /// Estimates the curvature at every vertex with the cotangent Laplacian and
+    /// angle deficits over mixed Voronoi areas. Principal directions come from
+    /// a least-squares fit of the normal curvatures along each edge.
+    ///
+    /// Vertices on open boundaries measure their angle deficit against a half
+    /// turn rather than a full one.
+    pub fn curvatures(&self) -> Vec<Curvature<V::Scalar>> {
+        let zero = V::Scalar::ZERO;
+        let one = V::Scalar::ONE;
+        let half_turn = (-one).acos();
+        let pos = self.verts.iter().map(|v| v.pos()).collect::<Vec<_>>();
+        let n = pos.len();
+
+        let mut area = vec![zero; n];
+        let mut angle = vec![zero; n];
+        let mut laplacian = vec![Vect::ZERO; n];
+        let mut normal = vec![Vect::ZERO; n];
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); n];
+
+        for tri in self.tris.iter() {
+            let [a, b, c] = tri.map(|i| pos[i]);
+            let cross = (b - a).cross(c - a);
+            let tri_area = cross.magn() / int(2);
+            if tri_area == zero {
+                continue;
+            }
+            let obtuse = (0..3).find(|&i| {
+                let p = pos[tri[i]];
+                (pos[tri[(i + 1) % 3]] - p).dot(pos[tri[(i + 2) % 3]] - p) < zero
+            });
+
+            for i in 0..3 {
+                let (v, j, k) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+                let e0 = pos[j] - pos[v];
+                let e1 = pos[k] - pos[v];
+                normal[v] = normal[v] + cross;
+                angle[v] = angle[v] + angle_between(e0.normal_or_zero(), e1.normal_or_zero());
+                neighbours[v].extend([j, k]);
+
+                // The cotangent at this corner weighs the opposite edge.
+                let cot = e0.dot(e1) / (tri_area * int(2));
+                let edge = pos[k] - pos[j];
+                laplacian[j] = laplacian[j] + edge * cot;
+                laplacian[k] = laplacian[k] - edge * cot;
+
+                area[v] = area[v]
+                    + match obtuse {
+                        None => {
+                            let cot_j =
+                                (pos[k] - pos[j]).dot(pos[v] - pos[j]) / (tri_area * int(2));
+                            let cot_k =
+                                (pos[v] - pos[k]).dot(pos[j] - pos[k]) / (tri_area * int(2));
+                            (e1.sqr_magn() * cot_j + e0.sqr_magn() * cot_k) / int(8)
+                        }
+                        Some(o) if o == i => tri_area / int(2),
+                        Some(_) => tri_area / int(4),
+                    };
+            }
+        }
+
+        let mut boundary = vec![false; n];
+        for v in self.boundary_verts() {
+            boundary[v] = true;
+        }
+
+        (0..n)
+            .map(|v| {
+                let normal = normal[v].normal_or_zero();
+                if area[v] == zero {
+                    return Curvature {
+                        mean: zero,
+                        gaussian: zero,
+                        principal: [zero; 2],
+                        directions: [Vect::ZERO; 2],
+                    };
+                }
+
+                let full = if boundary[v] {
+                    half_turn
+                } else {
+                    half_turn * int(2)
+                };
+                let gaussian = (full - angle[v]) / area[v];
+                let mean = -laplacian[v].dot(normal) / (area[v] * int(4));
+                let discriminant = mean * mean - gaussian;
+                let spread = if discriminant > zero {
+                    discriminant.sqrt()
+                } else {
+                    zero
+                };
+
+                Curvature {
+                    mean,
+                    gaussian,
+                    principal: [mean + spread, mean - spread],
+                    directions: self.principal_directions(&pos, v, normal, &neighbours[v]),
+                }
+            })
+            .collect()
+    }
+
+    /// Fits the second fundamental form at `v` to the normal curvatures along
+    /// its edges and returns its eigenvectors, largest curvature first.
+    fn principal_directions(
+        &self,
+        pos: &[Vect<3, V::Scalar>],
+        v: usize,
+        normal: Vect<3, V::Scalar>,
+        neighbours: &[usize],
+    ) -> [Vect<3, V::Scalar>; 2] {
+        let zero = V::Scalar::ZERO;
+        let two = V::Scalar::ONE + V::Scalar::ONE;
+        let tangent = |d: Vect<3, V::Scalar>| d - normal * normal.dot(d);
+
+        let Some(e1) = neighbours
+            .iter()
+            .map(|&j| tangent(pos[j] - pos[v]).normal_or_zero())
+            .find(|e| *e != Vect::ZERO)
+        else {
+            return [Vect::ZERO; 2];
+        };
+        let e2 = normal.cross(e1);
+
+        let mut m = [[zero; 3]; 3];
+        let mut r = [zero; 3];
+        for &j in neighbours {
+            let d = pos[j] - pos[v];
+            let length = d.sqr_magn();
+            let t = tangent(d).normal_or_zero();
+            if length == zero || t == Vect::ZERO {
+                continue;
+            }
+            let kappa = -two * d.dot(normal) / length;
+            let (x, y) = (t.dot(e1), t.dot(e2));
+            let f = [x * x, two * x * y, y * y];
+            for a in 0..3 {
+                for b in 0..3 {
+                    m[a][b] = m[a][b] + f[a] * f[b];
+                }
+                r[a] = r[a] + f[a] * kappa;
+            }
+        }
+
+        let det = det3(m);
+        if det == zero {
+            return [e1, e2];
+        }
+        let [a, b, c] = [0, 1, 2].map(|col| {
+            let mut mc = m;
+            for row in 0..3 {
+                mc[row][col] = r[row];
+            }
+            det3(mc) / det
+        });
+
+        // The eigenvector of [[a, b], [b, c]] for its larger eigenvalue.
+        let half_gap = (a - c) / two;
+        let largest = (a + c) / two + (half_gap * half_gap + b * b).sqrt();
+        let u = e1 * b + e2 * (largest - a);
+        let w = e1 * (largest - c) + e2 * b;
+        let major = (if u.sqr_magn() > w.sqr_magn() { u } else { w }).normal_or_zero();
+        if major == Vect::ZERO {
+            return [e1, e2];
+        }
+        [major, normal.cross(major)]
+    }
+}
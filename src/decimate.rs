@@ -0,0 +1,339 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use ear_algae::{
+    prelude::*,
+    traits::{Field, Ring},
+};
+
+use crate::{scalar::int, vertex::VertexPosition, Mesh};
+
+/// When to stop simplifying.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DecimationTarget<S> {
+    /// Collapse edges until at most this many triangles remain.
+    TriCount(usize),
+    /// Collapse edges while the cheapest collapse costs no more than this.
+    /// The cost of a collapse is the sum of squared distances from the merged
+    /// vertex to the planes of the original triangles around both its ends, so
+    /// it scales with the square of the mesh's size.
+    MaxError(S),
+}
+
+/// A plane quadric, stored as the upper triangle of a symmetric 4×4 matrix.
+#[derive(Copy, Clone, Debug)]
+struct Quadric<S: Field>([S; 10]);
+
+impl<S: Field> Quadric<S> {
+    fn zero() -> Self {
+        Self([S::ZERO; 10])
+    }
+
+    fn plane(normal: Vect<3, S>, d: S) -> Self {
+        let [a, b, c] = normal.0;
+        Self([
+            a * a,
+            a * b,
+            a * c,
+            a * d,
+            b * b,
+            b * c,
+            b * d,
+            c * c,
+            c * d,
+            d * d,
+        ])
+    }
+
+    fn add(self, other: Self) -> Self {
+        let mut sum = self.0;
+        for (s, o) in sum.iter_mut().zip(other.0) {
+            *s = *s + o;
+        }
+        Self(sum)
+    }
+
+    fn error(&self, p: Vect<3, S>) -> S {
+        let [x, y, z] = p.0;
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, dd] = self.0;
+        let two = S::ONE + S::ONE;
+        aa * x * x
+            + bb * y * y
+            + cc * z * z
+            + dd
+            + two * (ab * x * y + ac * x * z + ad * x + bc * y * z + bd * y + cd * z)
+    }
+}
+
+struct Candidate<S> {
+    cost: S,
+    edge: [usize; 2],
+    stamps: [usize; 2],
+}
+
+impl<S: PartialOrd> PartialEq for Candidate<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S: PartialOrd> Eq for Candidate<S> {}
+
+impl<S: PartialOrd> PartialOrd for Candidate<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: PartialOrd> Ord for Candidate<S> {
+    /// Reversed, so that the cheapest collapse sits on top of the heap.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.edge.cmp(&self.edge))
+    }
+}
+
+struct Decimator<V: VertexPosition> {
+    verts: Vec<V>,
+    tris: Vec<Option<[usize; 3]>>,
+    vert_tris: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric<V::Scalar>>,
+    stamps: Vec<usize>,
+    locked: Vec<bool>,
+    boundary: Vec<bool>,
+    live_tris: usize,
+}
+
+impl<V: VertexPosition> Decimator<V>
+where
+    V::Scalar: PartialOrd,
+{
+    fn neighbours(&self, v: usize) -> Vec<usize> {
+        let mut neighbours = self.vert_tris[v]
+            .iter()
+            .filter_map(|&t| self.tris[t])
+            .flatten()
+            .filter(|&w| w != v)
+            .collect::<Vec<_>>();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    /// The cheapest position for the merged vertex of `edge`, and its cost.
+    fn placement(&self, [u, v]: [usize; 2]) -> Option<(V::Scalar, Vect<3, V::Scalar>)> {
+        let quadric = self.quadrics[u].add(self.quadrics[v]);
+        let (pu, pv) = (self.verts[u].pos(), self.verts[v].pos());
+        let options = match (self.locked[u], self.locked[v]) {
+            (true, true) => return None,
+            (true, false) => vec![pu],
+            (false, true) => vec![pv],
+            (false, false) => vec![pu, pv, (pu + pv) * (V::Scalar::ONE / int(2))],
+        };
+        options
+            .into_iter()
+            .map(|p| (quadric.error(p), p))
+            .reduce(|best, next| if next.0 < best.0 { next } else { best })
+    }
+
+    fn push_candidates(&self, v: usize, heap: &mut BinaryHeap<Candidate<V::Scalar>>) {
+        for w in self.neighbours(v) {
+            let edge = [v.min(w), v.max(w)];
+            if let Some((cost, _)) = self.placement(edge) {
+                heap.push(Candidate {
+                    cost,
+                    edge,
+                    stamps: edge.map(|i| self.stamps[i]),
+                });
+            }
+        }
+    }
+
+    /// Whether the edge can be collapsed without pinching the surface: the two
+    /// vertices may only share the neighbours opposite the edge, and an interior
+    /// edge may not join two boundary vertices.
+    fn link_condition(&self, [u, v]: [usize; 2]) -> bool {
+        let shared_tris = self.vert_tris[u]
+            .iter()
+            .filter(|&&t| self.tris[t].is_some_and(|tri| tri.contains(&v)))
+            .count();
+        if shared_tris > 1 && self.boundary[u] && self.boundary[v] {
+            return false;
+        }
+        let nu = self.neighbours(u);
+        let shared = self
+            .neighbours(v)
+            .into_iter()
+            .filter(|w| nu.contains(w))
+            .count();
+        shared == shared_tris
+    }
+
+    /// Whether moving `from` to `to` at `p` would turn any surviving triangle
+    /// of `from` over. Triangles that already have no area can't be turned
+    /// over, so they don't count.
+    fn flips(&self, from: usize, to: usize, p: Vect<3, V::Scalar>) -> bool {
+        self.vert_tris[from].iter().any(|&t| {
+            let Some(tri) = self.tris[t] else {
+                return false;
+            };
+            if tri.contains(&to) {
+                return false;
+            }
+            let before = tri.map(|i| self.verts[i].pos());
+            let after = tri.map(|i| if i == from { p } else { self.verts[i].pos() });
+            let normal = |[a, b, c]: [Vect<3, V::Scalar>; 3]| (b - a).cross(c - a);
+            let before = normal(before);
+            before != Vect::ZERO && before.dot(normal(after)) <= V::Scalar::ZERO
+        })
+    }
+
+    fn collapse(&mut self, [u, v]: [usize; 2], p: Vect<3, V::Scalar>) {
+        let (keep, gone) = if self.locked[v] { (v, u) } else { (u, v) };
+        self.verts[keep].set_pos(p);
+        self.quadrics[keep] = self.quadrics[keep].add(self.quadrics[gone]);
+        self.boundary[keep] |= self.boundary[gone];
+
+        for t in std::mem::take(&mut self.vert_tris[gone]) {
+            let Some(mut tri) = self.tris[t] else {
+                continue;
+            };
+            if tri.contains(&keep) {
+                self.tris[t] = None;
+                self.live_tris -= 1;
+            } else {
+                tri.iter_mut()
+                    .filter(|i| **i == gone)
+                    .for_each(|i| *i = keep);
+                self.tris[t] = Some(tri);
+                self.vert_tris[keep].push(t);
+            }
+        }
+        let tris = &self.tris;
+        self.vert_tris[keep].retain(|&t| tris[t].is_some());
+        self.stamps[keep] += 1;
+        self.stamps[gone] += 1;
+    }
+}
+
+impl<V: VertexPosition> Mesh<V>
+where
+    V::Scalar: PartialOrd,
+{
+    /// Simplifies the mesh by quadric error edge collapses.
+    ///
+    /// With `preserve_boundary`, vertices on open boundaries never move, so the
+    /// outline of the mesh survives intact. Collapses that would flip a triangle
+    /// or pinch the surface are skipped.
+    pub fn decimate(self, target: DecimationTarget<V::Scalar>, preserve_boundary: bool) -> Self {
+        let mut boundary = vec![false; self.verts.len()];
+        for [a, b] in self.boundary_edges() {
+            boundary[a] = true;
+            boundary[b] = true;
+        }
+        let locked = if preserve_boundary {
+            boundary.clone()
+        } else {
+            vec![false; self.verts.len()]
+        };
+
+        let mut quadrics = vec![Quadric::zero(); self.verts.len()];
+        let mut vert_tris = vec![Vec::new(); self.verts.len()];
+        for (t, tri) in self.tris.iter().enumerate() {
+            let [a, b, c] = tri.map(|i| self.verts[i].pos());
+            if let Some(normal) = (b - a).cross(c - a).normal() {
+                let normal = Vect::from(normal);
+                let plane = Quadric::plane(normal, -normal.dot(a));
+                for &i in tri {
+                    quadrics[i] = quadrics[i].add(plane);
+                }
+            }
+            for &i in tri {
+                vert_tris[i].push(t);
+            }
+        }
+
+        let mut decimator = Decimator {
+            live_tris: self.tris.len(),
+            tris: self.tris.iter().copied().map(Some).collect(),
+            verts: self.verts.into_vec(),
+            vert_tris,
+            quadrics,
+            stamps: vec![0; locked.len()],
+            locked,
+            boundary,
+        };
+
+        let mut heap = BinaryHeap::new();
+        for v in 0..decimator.verts.len() {
+            decimator.push_candidates(v, &mut heap);
+        }
+
+        while let Some(Candidate { cost, edge, stamps }) = heap.pop() {
+            match target {
+                DecimationTarget::TriCount(count) if decimator.live_tris <= count => break,
+                DecimationTarget::MaxError(max) if cost > max => break,
+                _ => {}
+            }
+            if edge.map(|i| decimator.stamps[i]) != stamps || !decimator.link_condition(edge) {
+                continue;
+            }
+            let Some((_, p)) = decimator.placement(edge) else {
+                continue;
+            };
+            let [u, v] = edge;
+            if decimator.flips(u, v, p) || decimator.flips(v, u, p) {
+                continue;
+            }
+            decimator.collapse(edge, p);
+            let keep = if decimator.locked[v] { v } else { u };
+            decimator.push_candidates(keep, &mut heap);
+        }
+
+        let Decimator { verts, tris, .. } = decimator;
+        Mesh {
+            verts: verts.into_boxed_slice(),
+            tris: tris.into_iter().flatten().collect(),
+        }
+        .without_unused_verts()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ear_algae::Vect;
+
+    use super::{Decimator, Quadric};
+
+    /// A zero-area triangle `[0, 1, 2]` along the x axis, and a triangle
+    /// `[0, 2, 3]` facing up.
+    fn decimator() -> Decimator<Vect<3, f64>> {
+        Decimator {
+            verts: vec![
+                Vect([0., 0., 0.]),
+                Vect([1., 0., 0.]),
+                Vect([2., 0., 0.]),
+                Vect([1., 1., 0.]),
+            ],
+            tris: vec![Some([0, 1, 2]), Some([0, 2, 3])],
+            vert_tris: vec![vec![0, 1], vec![0], vec![0, 1], vec![1]],
+            quadrics: vec![Quadric::zero(); 4],
+            stamps: vec![0; 4],
+            locked: vec![false; 4],
+            boundary: vec![false; 4],
+            live_tris: 2,
+        }
+    }
+
+    #[test]
+    fn zero_area_triangles_do_not_flip() {
+        assert!(!decimator().flips(1, 3, Vect([1., 1., 0.])));
+    }
+
+    #[test]
+    fn moving_across_an_edge_flips() {
+        assert!(decimator().flips(3, 1, Vect([1., -1., 0.])));
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use ear_algae::{
+    prelude::*,
+    traits::{Field, Ring},
+};
+
+use crate::{
+    bvh::{Aabb, Bvh, NodeKind},
+    scalar::angle_between,
+    vertex::VertexPosition,
+    Mesh,
+};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ClosestPoint<S: Field> {
+    pub point: Vect<3, S>,
+    pub tri: usize,
+    /// Weights of the triangle's three corners at `point`.
+    pub bary: [S; 3],
+    pub sqr_distance: S,
+}
+
+impl<S: Field + PartialOrd> Aabb<S> {
+    pub fn sqr_distance(&self, p: Vect<3, S>) -> S {
+        (0..3).fold(S::ZERO, |acc, i| {
+            let d = if p[i] < self.min[i] {
+                self.min[i] - p[i]
+            } else if p[i] > self.max[i] {
+                p[i] - self.max[i]
+            } else {
+                S::ZERO
+            };
+            acc + d * d
+        })
+    }
+}
+
+/// The point of triangle `abc` nearest to `p`, with its barycentric weights.
+///
+/// Weights are exactly zero when the point lies on an edge or corner.
+fn closest_on_tri<S: Field + PartialOrd>(
+    p: Vect<3, S>,
+    [a, b, c]: [Vect<3, S>; 3],
+) -> (Vect<3, S>, [S; 3]) {
+    let zero = S::ZERO;
+    let one = S::ONE;
+    let ab = b - a;
+    let ac = c - a;
+
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= zero && d2 <= zero {
+        return (a, [one, zero, zero]);
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= zero && d4 <= d3 {
+        return (b, [zero, one, zero]);
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= zero && d1 >= zero && d3 <= zero {
+        let v = d1 / (d1 - d3);
+        return (a + ab * v, [one - v, v, zero]);
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= zero && d5 <= d6 {
+        return (c, [zero, zero, one]);
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= zero && d2 >= zero && d6 <= zero {
+        let w = d2 / (d2 - d6);
+        return (a + ac * w, [one - w, zero, w]);
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= zero && d4 - d3 >= zero && d5 - d6 >= zero {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (b + (c - b) * w, [zero, one - w, w]);
+    }
+
+    let denom = one / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    (a + ab * v + ac * w, [one - v - w, v, w])
+}
+
+impl<S: Field + PartialOrd> Bvh<S> {
+    pub fn closest_point(&self, p: Vect<3, S>) -> Option<ClosestPoint<S>> {
+        let mut best: Option<ClosestPoint<S>> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if best.is_some_and(|b| node.bounds.sqr_distance(p) >= b.sqr_distance) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf { start, end } => {
+                    for &t in &self.order[start..end] {
+                        let (point, bary) = closest_on_tri(p, self.tris[t]);
+                        let sqr_distance = (point - p).sqr_magn();
+                        if best.is_none_or(|b| sqr_distance < b.sqr_distance) {
+                            best = Some(ClosestPoint {
+                                point,
+                                tri: t,
+                                bary,
+                                sqr_distance,
+                            });
+                        }
+                    }
+                }
+                NodeKind::Branch { left, right } => {
+                    let near_left = self.nodes[left].bounds.sqr_distance(p)
+                        <= self.nodes[right].bounds.sqr_distance(p);
+                    stack.extend(if near_left {
+                        [right, left]
+                    } else {
+                        [left, right]
+                    });
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Closest-point and signed-distance queries against a mesh.
+///
+/// The sign comes from angle-weighted pseudo-normals, so it is only reliable
+/// for closed, consistently oriented meshes; distances are positive outside.
+#[derive(Clone, Debug)]
+pub struct DistanceField<S: Field> {
+    bvh: Bvh<S>,
+    tris: Vec<[usize; 3]>,
+    face_normals: Vec<Vect<3, S>>,
+    edge_normals: HashMap<[usize; 2], Vect<3, S>>,
+    vert_normals: Vec<Vect<3, S>>,
+}
+
+impl<S: Field + PartialOrd> DistanceField<S> {
+    pub fn new<V: VertexPosition<Scalar = S>>(mesh: &Mesh<V>) -> Self {
+        let face_normals = mesh
+            .tri_verts()
+            .into_iter()
+            .map(|t| t.normal().map_or(Vect::ZERO, Vect::from))
+            .collect::<Vec<_>>();
+
+        let mut edge_normals: HashMap<[usize; 2], Vect<3, S>> = HashMap::new();
+        let mut vert_normals = vec![Vect::ZERO; mesh.verts.len()];
+        for (tri, &normal) in mesh.tris.iter().zip(face_normals.iter()) {
+            for i in 0..3 {
+                let (a, b) = (tri[i], tri[(i + 1) % 3]);
+                *edge_normals
+                    .entry([a.min(b), a.max(b)])
+                    .or_insert(Vect::ZERO) += normal;
+
+                let e0 = mesh.verts[tri[(i + 1) % 3]].pos() - mesh.verts[tri[i]].pos();
+                let e1 = mesh.verts[tri[(i + 2) % 3]].pos() - mesh.verts[tri[i]].pos();
+                let denom = e0.magn() * e1.magn();
+                if denom != S::ZERO {
+                    let angle = angle_between(e0.normal_or_zero(), e1.normal_or_zero());
+                    vert_normals[tri[i]] += normal * angle;
+                }
+            }
+        }
+
+        Self {
+            bvh: mesh.bvh(),
+            tris: mesh.tris.to_vec(),
+            face_normals,
+            edge_normals,
+            vert_normals,
+        }
+    }
+
+    pub fn bvh(&self) -> &Bvh<S> {
+        &self.bvh
+    }
+
+    pub fn closest_point(&self, p: Vect<3, S>) -> Option<ClosestPoint<S>> {
+        self.bvh.closest_point(p)
+    }
+
+    /// The pseudo-normal of the feature of triangle `t` that the weights
+    /// `bary` lie on: a corner, an edge or the face itself.
+    fn pseudo_normal(&self, t: usize, bary: [S; 3]) -> Vect<3, S> {
+        let tri = self.tris[t];
+        let zeros = bary.iter().filter(|&&w| w == S::ZERO).count();
+        match zeros {
+            2 => {
+                let corner = (0..3).find(|&i| bary[i] != S::ZERO).unwrap_or(0);
+                self.vert_normals[tri[corner]]
+            }
+            1 => {
+                let skip = (0..3).find(|&i| bary[i] == S::ZERO).unwrap_or(0);
+                let (a, b) = (tri[(skip + 1) % 3], tri[(skip + 2) % 3]);
+                self.edge_normals[&[a.min(b), a.max(b)]]
+            }
+            _ => self.face_normals[t],
+        }
+    }
+
+    /// The distance from `p` to the surface, negative inside. `None` for an
+    /// empty mesh.
+    pub fn signed_distance(&self, p: Vect<3, S>) -> Option<S> {
+        let closest = self.closest_point(p)?;
+        let distance = closest.sqr_distance.sqrt();
+        let normal = self.pseudo_normal(closest.tri, closest.bary);
+        Some(if (p - closest.point).dot(normal) < S::ZERO {
+            -distance
+        } else {
+            distance
+        })
+    }
+}
+
+impl<V: VertexPosition> Mesh<V>
+where
+    V::Scalar: PartialOrd,
+{
+    pub fn distance_field(&self) -> DistanceField<V::Scalar> {
+        DistanceField::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ear_algae::Vect;
+
+    use crate::Mesh;
+
+    /// A tetrahedron with vertex 4 splitting edge 0-1, closed up by a
+    /// zero-area sliver whose corner at 0 sees two edges along (1, 1, 1),
+    /// where the cosine rounds just past one.
+    fn split_tetrahedron() -> Mesh<Vect<3, f64>> {
+        Mesh {
+            verts: [
+                [0., 0., 0.],
+                [2., 2., 2.],
+                [2., 0., 0.],
+                [0., 2., 0.],
+                [1., 1., 1.],
+            ]
+            .map(Vect)
+            .into(),
+            tris: [
+                [0, 1, 3],
+                [0, 3, 2],
+                [1, 2, 3],
+                [2, 1, 4],
+                [2, 4, 0],
+                [0, 4, 1],
+            ]
+            .into(),
+        }
+    }
+
+    #[test]
+    fn near_degenerate_corner_keeps_pseudo_normals_finite() {
+        let field = split_tetrahedron().distance_field();
+        for normal in &field.vert_normals {
+            assert!((0..3).all(|i| normal[i].is_finite()));
+        }
+        assert!(field.signed_distance(Vect([1., 1., 0.5])).unwrap() < 0.);
+        let outside = field.signed_distance(Vect([-1., -1., -1.])).unwrap();
+        assert!((outside - 3f64.sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+
+use crate::Mesh;
+
+mod edit;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct VertexHandle(pub usize);
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct HalfEdgeHandle(pub usize);
+
+/// An undirected edge, identified by the lower of its two half-edges.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct EdgeHandle(pub HalfEdgeHandle);
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct FaceHandle(pub usize);
+
+#[derive(Copy, Clone, Debug)]
+struct HalfEdge {
+    origin: VertexHandle,
+    twin: Option<HalfEdgeHandle>,
+}
+
+/// Half-edge connectivity over the triangles of a [`Mesh`].
+///
+/// The half-edges of face `f` are `3f`, `3f + 1` and `3f + 2`, in winding order.
+/// Edges shared by more than two triangles are left without twins, so they read
+/// as boundary from every side.
+///
+/// Faces and vertices removed by edits keep their slots until the mesh is
+/// turned back into a [`Mesh`], so handles to everything else stay valid.
+#[derive(Clone, Debug)]
+pub struct HalfEdgeMesh<V: Copy> {
+    verts: Vec<V>,
+    outgoing: Vec<Option<HalfEdgeHandle>>,
+    half_edges: Vec<HalfEdge>,
+    removed_verts: Vec<bool>,
+    removed_faces: Vec<bool>,
+    /// Live half-edges leaving each vertex, whether circulation reaches them
+    /// or not.
+    corners: Vec<usize>,
+    /// Vertices whose faces form more than one fan, which circulation can't
+    /// see all of.
+    multi_fan: Vec<bool>,
+}
+
+impl<V: Copy> HalfEdgeMesh<V> {
+    pub fn new(mesh: &Mesh<V>) -> Self {
+        Self::from_parts(mesh.verts.to_vec(), &mesh.tris)
+    }
+
+    fn from_parts(verts: Vec<V>, tris: &[[usize; 3]]) -> Self {
+        let mut half_edges = Vec::with_capacity(tris.len() * 3);
+        let mut directed: HashMap<(usize, usize), Vec<HalfEdgeHandle>> = HashMap::new();
+
+        for tri in tris {
+            for i in 0..3 {
+                let h = HalfEdgeHandle(half_edges.len());
+                half_edges.push(HalfEdge {
+                    origin: VertexHandle(tri[i]),
+                    twin: None,
+                });
+                directed
+                    .entry((tri[i], tri[(i + 1) % 3]))
+                    .or_default()
+                    .push(h);
+            }
+        }
+
+        for (&(a, b), forward) in directed.iter() {
+            if a > b {
+                continue;
+            }
+            if let Some(backward) = directed.get(&(b, a)) {
+                if let ([h], [t]) = (forward.as_slice(), backward.as_slice()) {
+                    half_edges[h.0].twin = Some(*t);
+                    half_edges[t.0].twin = Some(*h);
+                }
+            }
+        }
+
+        let mut this = Self {
+            outgoing: vec![None; verts.len()],
+            removed_verts: vec![false; verts.len()],
+            removed_faces: vec![false; tris.len()],
+            corners: vec![0; verts.len()],
+            multi_fan: vec![false; verts.len()],
+            verts,
+            half_edges,
+        };
+        this.rebuild_outgoing();
+
+        for he in &this.half_edges {
+            this.corners[he.origin.0] += 1;
+        }
+        for v in 0..this.verts.len() {
+            this.update_multi_fan(VertexHandle(v));
+        }
+        this
+    }
+
+    fn update_multi_fan(&mut self, v: VertexHandle) {
+        self.multi_fan[v.0] = self.vertex_outgoing(v).count() < self.corners[v.0];
+    }
+
+    /// Picks an outgoing half-edge for every vertex, preferring one without a
+    /// twin so that circulation around boundary vertices starts at the boundary.
+    fn rebuild_outgoing(&mut self) {
+        self.outgoing.iter_mut().for_each(|o| *o = None);
+        for (i, he) in self.half_edges.iter().enumerate() {
+            if self.removed_faces[i / 3] {
+                continue;
+            }
+            let replace = match self.outgoing[he.origin.0] {
+                None => true,
+                Some(existing) => he.twin.is_none() && self.half_edges[existing.0].twin.is_some(),
+            };
+            if replace {
+                self.outgoing[he.origin.0] = Some(HalfEdgeHandle(i));
+            }
+        }
+    }
+
+    /// Converts back to a [`Mesh`]. Vertex indices are only renumbered if
+    /// vertices have been removed.
+    pub fn into_mesh(self) -> Mesh<V> {
+        let mut index_map = Vec::with_capacity(self.verts.len());
+        let mut verts = Vec::with_capacity(self.verts.len());
+        for (&v, &removed) in self.verts.iter().zip(self.removed_verts.iter()) {
+            index_map.push(verts.len());
+            if !removed {
+                verts.push(v);
+            }
+        }
+        let tris = self
+            .faces()
+            .map(|f| self.face_verts(f).map(|v| index_map[v.0]))
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+        Mesh {
+            verts: verts.into_boxed_slice(),
+            tris,
+        }
+    }
+
+    /// All vertex slots, including those of removed vertices.
+    pub fn verts(&self) -> &[V] {
+        &self.verts
+    }
+
+    pub fn verts_mut(&mut self) -> &mut [V] {
+        &mut self.verts
+    }
+
+    pub fn vert(&self, v: VertexHandle) -> V {
+        self.verts[v.0]
+    }
+
+    pub fn vert_count(&self) -> usize {
+        self.removed_verts.iter().filter(|&&r| !r).count()
+    }
+
+    pub fn face_count(&self) -> usize {
+        self.removed_faces.iter().filter(|&&r| !r).count()
+    }
+
+    /// Whether `f` is still part of the mesh, rather than removed by an edit.
+    pub fn contains_face(&self, f: FaceHandle) -> bool {
+        !self.removed_faces[f.0]
+    }
+
+    pub fn vertices(&self) -> impl Iterator<Item = VertexHandle> + '_ {
+        (0..self.verts.len())
+            .filter(|&v| !self.removed_verts[v])
+            .map(VertexHandle)
+    }
+
+    pub fn faces(&self) -> impl Iterator<Item = FaceHandle> + '_ {
+        (0..self.removed_faces.len())
+            .filter(|&f| !self.removed_faces[f])
+            .map(FaceHandle)
+    }
+
+    pub fn half_edges(&self) -> impl Iterator<Item = HalfEdgeHandle> + '_ {
+        self.faces().flat_map(|f| self.face_half_edges(f))
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = EdgeHandle> + '_ {
+        self.half_edges()
+            .filter(|&h| self.twin(h).is_none_or(|t| h < t))
+            .map(EdgeHandle)
+    }
+
+    pub fn origin(&self, h: HalfEdgeHandle) -> VertexHandle {
+        self.half_edges[h.0].origin
+    }
+
+    pub fn target(&self, h: HalfEdgeHandle) -> VertexHandle {
+        self.origin(self.next(h))
+    }
+
+    pub fn twin(&self, h: HalfEdgeHandle) -> Option<HalfEdgeHandle> {
+        self.half_edges[h.0].twin
+    }
+
+    pub fn next(&self, h: HalfEdgeHandle) -> HalfEdgeHandle {
+        HalfEdgeHandle(h.0 - h.0 % 3 + (h.0 + 1) % 3)
+    }
+
+    pub fn prev(&self, h: HalfEdgeHandle) -> HalfEdgeHandle {
+        HalfEdgeHandle(h.0 - h.0 % 3 + (h.0 + 2) % 3)
+    }
+
+    pub fn face(&self, h: HalfEdgeHandle) -> FaceHandle {
+        FaceHandle(h.0 / 3)
+    }
+
+    pub fn is_boundary(&self, h: HalfEdgeHandle) -> bool {
+        self.twin(h).is_none()
+    }
+
+    pub fn is_boundary_vertex(&self, v: VertexHandle) -> bool {
+        self.outgoing(v).is_none_or(|h| self.is_boundary(h))
+    }
+
+    /// An outgoing half-edge of `v`, or `None` if `v` is not part of any face.
+    pub fn outgoing(&self, v: VertexHandle) -> Option<HalfEdgeHandle> {
+        self.outgoing[v.0]
+    }
+
+    pub fn face_half_edges(&self, f: FaceHandle) -> [HalfEdgeHandle; 3] {
+        [3 * f.0, 3 * f.0 + 1, 3 * f.0 + 2].map(HalfEdgeHandle)
+    }
+
+    pub fn face_verts(&self, f: FaceHandle) -> [VertexHandle; 3] {
+        self.face_half_edges(f).map(|h| self.origin(h))
+    }
+
+    /// The faces across each edge of `f`, in the order of [`Self::face_half_edges`].
+    pub fn face_neighbours(&self, f: FaceHandle) -> [Option<FaceHandle>; 3] {
+        self.face_half_edges(f)
+            .map(|h| self.twin(h).map(|t| self.face(t)))
+    }
+
+    pub fn edge_half_edges(&self, e: EdgeHandle) -> (HalfEdgeHandle, Option<HalfEdgeHandle>) {
+        (e.0, self.twin(e.0))
+    }
+
+    pub fn edge_verts(&self, e: EdgeHandle) -> [VertexHandle; 2] {
+        [self.origin(e.0), self.target(e.0)]
+    }
+
+    pub fn edge_faces(&self, e: EdgeHandle) -> (FaceHandle, Option<FaceHandle>) {
+        (self.face(e.0), self.twin(e.0).map(|t| self.face(t)))
+    }
+
+    pub fn edge(&self, h: HalfEdgeHandle) -> EdgeHandle {
+        match self.twin(h) {
+            Some(t) if t < h => EdgeHandle(t),
+            _ => EdgeHandle(h),
+        }
+    }
+
+    /// Finds the half-edge running from `a` to `b`, if there is one.
+    pub fn find_half_edge(&self, a: VertexHandle, b: VertexHandle) -> Option<HalfEdgeHandle> {
+        self.vertex_outgoing(a).find(|&h| self.target(h) == b)
+    }
+
+    /// Circulates the half-edges leaving `v`.
+    ///
+    /// Around a boundary vertex the circulation starts at the boundary and stops
+    /// at the other side of the gap.
+    pub fn vertex_outgoing(&self, v: VertexHandle) -> VertexCirculator<'_, V> {
+        VertexCirculator {
+            mesh: self,
+            start: self.outgoing(v),
+            current: self.outgoing(v),
+        }
+    }
+
+    /// The half-edges arriving at `v`, one per face around it.
+    pub fn vertex_incoming(&self, v: VertexHandle) -> impl Iterator<Item = HalfEdgeHandle> + '_ {
+        self.vertex_outgoing(v).map(|h| self.prev(h))
+    }
+
+    pub fn vertex_faces(&self, v: VertexHandle) -> impl Iterator<Item = FaceHandle> + '_ {
+        self.vertex_outgoing(v).map(|h| self.face(h))
+    }
+
+    /// The one-ring of `v`. Around a boundary vertex both boundary neighbours
+    /// are included.
+    pub fn vertex_neighbours(&self, v: VertexHandle) -> impl Iterator<Item = VertexHandle> + '_ {
+        let mut last = None;
+        self.vertex_outgoing(v)
+            .map(Some)
+            .chain([None])
+            .filter_map(move |h| match h {
+                Some(h) => {
+                    last = Some(h);
+                    Some(self.target(h))
+                }
+                None => {
+                    let h = self.prev(last?);
+                    self.is_boundary(h).then(|| self.origin(h))
+                }
+            })
+    }
+
+    pub fn valence(&self, v: VertexHandle) -> usize {
+        self.vertex_neighbours(v).count()
+    }
+}
+
+pub struct VertexCirculator<'m, V: Copy> {
+    mesh: &'m HalfEdgeMesh<V>,
+    start: Option<HalfEdgeHandle>,
+    current: Option<HalfEdgeHandle>,
+}
+
+impl<V: Copy> Iterator for VertexCirculator<'_, V> {
+    type Item = HalfEdgeHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let h = self.current?;
+        self.current = self
+            .mesh
+            .twin(self.mesh.prev(h))
+            .filter(|&n| Some(n) != self.start);
+        Some(h)
+    }
+}
+
+impl<V: Copy> From<Mesh<V>> for HalfEdgeMesh<V> {
+    fn from(mesh: Mesh<V>) -> Self {
+        Self::from_parts(mesh.verts.into_vec(), &mesh.tris)
+    }
+}
+
+impl<V: Copy> From<HalfEdgeMesh<V>> for Mesh<V> {
+    fn from(mesh: HalfEdgeMesh<V>) -> Self {
+        mesh.into_mesh()
+    }
+}
+
+impl<V: Copy> Mesh<V> {
+    pub fn half_edge_mesh(&self) -> HalfEdgeMesh<V> {
+        HalfEdgeMesh::new(self)
+    }
+}
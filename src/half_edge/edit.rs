@@ -0,0 +1,534 @@
+use std::collections::HashSet;
+
+use ear_algae::traits::Ring;
+
+use crate::vertex::VertexBlend;
+
+use super::{FaceHandle, HalfEdge, HalfEdgeHandle, HalfEdgeMesh, VertexHandle};
+
+impl<V: Copy> HalfEdgeMesh<V> {
+    fn link(&mut self, a: Option<HalfEdgeHandle>, b: Option<HalfEdgeHandle>) {
+        if let Some(a) = a {
+            self.half_edges[a.0].twin = b;
+        }
+        if let Some(b) = b {
+            self.half_edges[b.0].twin = a;
+        }
+    }
+
+    /// Rewrites face `f` in place, pairing each new half-edge with the given twin.
+    fn set_face(&mut self, f: usize, verts: [VertexHandle; 3], twins: [Option<HalfEdgeHandle>; 3]) {
+        for i in 0..3 {
+            let h = HalfEdgeHandle(3 * f + i);
+            self.corners[self.half_edges[h.0].origin.0] -= 1;
+            self.corners[verts[i].0] += 1;
+            self.half_edges[h.0] = HalfEdge {
+                origin: verts[i],
+                twin: None,
+            };
+            self.link(Some(h), twins[i]);
+        }
+    }
+
+    fn add_face(&mut self, verts: [VertexHandle; 3], twins: [Option<HalfEdgeHandle>; 3]) -> usize {
+        let f = self.removed_faces.len();
+        self.removed_faces.push(false);
+        self.half_edges
+            .extend(verts.map(|origin| HalfEdge { origin, twin: None }));
+        // Count the new corners so that `set_face` can swap them like for like.
+        for v in verts {
+            self.corners[v.0] += 1;
+        }
+        self.set_face(f, verts, twins);
+        f
+    }
+
+    fn add_vert(&mut self, vert: V) -> VertexHandle {
+        self.verts.push(vert);
+        self.outgoing.push(None);
+        self.removed_verts.push(false);
+        self.corners.push(0);
+        self.multi_fan.push(false);
+        VertexHandle(self.verts.len() - 1)
+    }
+
+    /// Points `v` at one of `candidates` (or its current outgoing half-edge),
+    /// then turns back to the boundary if the vertex has one.
+    ///
+    /// Also rechecks whether `v` still has a single fan, since the edit may have
+    /// changed its faces.
+    fn settle_outgoing(&mut self, v: VertexHandle, candidates: &[HalfEdgeHandle]) {
+        let start = candidates
+            .iter()
+            .copied()
+            .chain(self.outgoing[v.0])
+            .find(|&h| !self.removed_faces[h.0 / 3] && self.origin(h) == v);
+        self.outgoing[v.0] = start.map(|start| {
+            let mut h = start;
+            while let Some(t) = self.twin(h) {
+                h = self.next(t);
+                if h == start {
+                    break;
+                }
+            }
+            h
+        });
+        self.update_multi_fan(v);
+    }
+
+    fn face_edges_of(&self, faces: impl IntoIterator<Item = usize>) -> Vec<HalfEdgeHandle> {
+        faces
+            .into_iter()
+            .flat_map(|f| self.face_half_edges(FaceHandle(f)))
+            .collect()
+    }
+
+    /// Whether [`Self::flip_edge`] can turn `h`: it must have a face on both
+    /// sides, and the new diagonal must not already exist.
+    pub fn can_flip(&self, h: HalfEdgeHandle) -> bool {
+        let Some(t) = self.twin(h) else {
+            return false;
+        };
+        let c = self.origin(self.prev(h));
+        let d = self.origin(self.prev(t));
+        c != d && self.find_half_edge(c, d).is_none() && self.find_half_edge(d, c).is_none()
+    }
+
+    /// Replaces the edge of `h` with the other diagonal of the quad formed by
+    /// its two faces. Returns `false` and leaves the mesh alone if that isn't
+    /// possible.
+    ///
+    /// The half-edges of the two faces are renumbered.
+    pub fn flip_edge(&mut self, h: HalfEdgeHandle) -> bool {
+        if !self.can_flip(h) {
+            return false;
+        }
+        let t = self.twin(h).unwrap(); // Checked by `can_flip`.
+        let (a, b) = (self.origin(h), self.target(h));
+        let c = self.origin(self.prev(h));
+        let d = self.origin(self.prev(t));
+        let (fa, fb) = (self.face(h).0, self.face(t).0);
+        let bc = self.twin(self.next(h));
+        let ca = self.twin(self.prev(h));
+        let ad = self.twin(self.next(t));
+        let db = self.twin(self.prev(t));
+
+        self.set_face(fa, [c, a, d], [ca, ad, None]);
+        self.set_face(fb, [d, b, c], [db, bc, Some(HalfEdgeHandle(3 * fa + 2))]);
+
+        let candidates = self.face_edges_of([fa, fb]);
+        for v in [a, b, c, d] {
+            self.settle_outgoing(v, &candidates);
+        }
+        true
+    }
+
+    /// Whether [`Self::collapse_edge`] can merge the ends of `h` without making
+    /// the mesh non-manifold.
+    ///
+    /// Both ends must have a single fan of faces, and the ends may only share
+    /// the neighbours opposite the edge. An interior vertex opposite the edge
+    /// also needs more than three neighbours, or its last two faces would fold
+    /// onto each other, as on a tetrahedron.
+    pub fn can_collapse(&self, h: HalfEdgeHandle) -> bool {
+        let (a, b) = (self.origin(h), self.target(h));
+        if self.multi_fan[a.0] || self.multi_fan[b.0] {
+            return false;
+        }
+        let mut opposite = vec![self.origin(self.prev(h))];
+        if let Some(t) = self.twin(h) {
+            if self.is_boundary_vertex(a) && self.is_boundary_vertex(b) {
+                return false;
+            }
+            opposite.push(self.origin(self.prev(t)));
+            let folds = |&o: &VertexHandle| !self.is_boundary_vertex(o) && self.valence(o) <= 3;
+            if opposite.iter().any(folds) {
+                return false;
+            }
+        }
+
+        let around_a = self.vertex_neighbours(a).collect::<HashSet<_>>();
+        let shared = self
+            .vertex_neighbours(b)
+            .filter(|w| around_a.contains(w))
+            .collect::<HashSet<_>>();
+        shared.len() == opposite.len() && opposite.iter().all(|o| shared.contains(o))
+    }
+}
+
+impl<V: VertexBlend> HalfEdgeMesh<V> {
+    /// Splits the edge of `h` at `t` along it, splitting the faces on either
+    /// side in two. Returns the new vertex.
+    ///
+    /// The half-edges of the split faces are renumbered.
+    pub fn split_edge(&mut self, h: HalfEdgeHandle, t: V::Scalar) -> VertexHandle {
+        let twin = self.twin(h);
+        let (a, b) = (self.origin(h), self.target(h));
+        let c = self.origin(self.prev(h));
+        let fa = self.face(h).0;
+        let bc = self.twin(self.next(h));
+        let ca = self.twin(self.prev(h));
+        let far_side = twin.map(|tw| {
+            (
+                self.face(tw).0,
+                self.origin(self.prev(tw)),
+                self.twin(self.next(tw)),
+                self.twin(self.prev(tw)),
+            )
+        });
+
+        let m = self.add_vert(V::blend(&[
+            (self.vert(a), V::Scalar::ONE - t),
+            (self.vert(b), t),
+        ]));
+
+        self.set_face(fa, [a, m, c], [None, None, ca]);
+        let fa2 = self.add_face([m, b, c], [None, bc, Some(HalfEdgeHandle(3 * fa + 1))]);
+        let mut faces = vec![fa, fa2];
+        let mut touched = vec![a, b, c, m];
+
+        if let Some((fb, d, ad, db)) = far_side {
+            self.set_face(fb, [b, m, d], [Some(HalfEdgeHandle(3 * fa2)), None, db]);
+            let fb2 = self.add_face(
+                [m, a, d],
+                [
+                    Some(HalfEdgeHandle(3 * fa)),
+                    ad,
+                    Some(HalfEdgeHandle(3 * fb + 1)),
+                ],
+            );
+            faces.extend([fb, fb2]);
+            touched.push(d);
+        }
+
+        let candidates = self.face_edges_of(faces);
+        for v in touched {
+            self.settle_outgoing(v, &candidates);
+        }
+        m
+    }
+
+    /// Splits face `f` into three around a new vertex at the barycentric
+    /// weights `bary`. Returns the new vertex.
+    pub fn split_face(&mut self, f: FaceHandle, bary: [V::Scalar; 3]) -> VertexHandle {
+        let [a, b, c] = self.face_verts(f);
+        let [ab, bc, ca] = self.face_half_edges(f).map(|h| self.twin(h));
+        let p = self.add_vert(V::blend(&[
+            (self.vert(a), bary[0]),
+            (self.vert(b), bary[1]),
+            (self.vert(c), bary[2]),
+        ]));
+
+        self.set_face(f.0, [a, b, p], [ab, None, None]);
+        let f1 = self.add_face([b, c, p], [bc, None, Some(HalfEdgeHandle(3 * f.0 + 1))]);
+        let f2 = self.add_face(
+            [c, a, p],
+            [
+                ca,
+                Some(HalfEdgeHandle(3 * f.0 + 2)),
+                Some(HalfEdgeHandle(3 * f1 + 1)),
+            ],
+        );
+
+        let candidates = self.face_edges_of([f.0, f1, f2]);
+        for v in [a, b, c, p] {
+            self.settle_outgoing(v, &candidates);
+        }
+        p
+    }
+
+    /// Merges the target of `h` into its origin, placed at `t` along the edge,
+    /// and removes the faces on either side. Returns the surviving vertex, or
+    /// `None` if the collapse fails the link condition.
+    pub fn collapse_edge(&mut self, h: HalfEdgeHandle, t: V::Scalar) -> Option<VertexHandle> {
+        if !self.can_collapse(h) {
+            return None;
+        }
+        let (a, b) = (self.origin(h), self.target(h));
+        let twin = self.twin(h);
+        let from_b = self.vertex_outgoing(b).collect::<Vec<_>>();
+        let mut faces = from_b.iter().map(|&h| self.face(h).0).collect::<Vec<_>>();
+        faces.extend(self.vertex_outgoing(a).map(|h| self.face(h).0));
+        let candidates = self.face_edges_of(faces);
+
+        let mut removed = vec![self.face(h)];
+        let mut touched = vec![a, self.origin(self.prev(h))];
+        self.link(self.twin(self.next(h)), self.twin(self.prev(h)));
+        if let Some(tw) = twin {
+            removed.push(self.face(tw));
+            touched.push(self.origin(self.prev(tw)));
+            self.link(self.twin(self.next(tw)), self.twin(self.prev(tw)));
+        }
+
+        self.corners[a.0] += from_b.len();
+        self.corners[b.0] = 0;
+        for h in from_b {
+            self.half_edges[h.0].origin = a;
+        }
+        for f in removed {
+            self.removed_faces[f.0] = true;
+            for h in self.face_half_edges(f) {
+                self.half_edges[h.0].twin = None;
+                self.corners[self.half_edges[h.0].origin.0] -= 1;
+            }
+        }
+        self.verts[a.0] = V::blend(&[(self.vert(a), V::Scalar::ONE - t), (self.vert(b), t)]);
+        self.removed_verts[b.0] = true;
+        self.outgoing[b.0] = None;
+
+        for v in touched {
+            self.settle_outgoing(v, &candidates);
+        }
+        Some(a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ear_algae::Vect;
+
+    use crate::{
+        half_edge::{FaceHandle, HalfEdgeMesh, VertexHandle},
+        Mesh,
+    };
+
+    fn mesh(verts: &[[f64; 3]], tris: &[[usize; 3]]) -> HalfEdgeMesh<Vect<3, f64>> {
+        HalfEdgeMesh::from(Mesh {
+            verts: verts.iter().map(|&p| Vect(p)).collect(),
+            tris: tris.into(),
+        })
+    }
+
+    fn square() -> HalfEdgeMesh<Vect<3, f64>> {
+        mesh(
+            &[[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]],
+            &[[0, 1, 2], [0, 2, 3]],
+        )
+    }
+
+    fn tetrahedron() -> HalfEdgeMesh<Vect<3, f64>> {
+        mesh(
+            &[[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            &[[0, 2, 1], [0, 1, 3], [1, 2, 3], [0, 3, 2]],
+        )
+    }
+
+    fn octahedron() -> HalfEdgeMesh<Vect<3, f64>> {
+        mesh(
+            &[
+                [1., 0., 0.],
+                [-1., 0., 0.],
+                [0., 1., 0.],
+                [0., -1., 0.],
+                [0., 0., 1.],
+                [0., 0., -1.],
+            ],
+            &[
+                [0, 2, 4],
+                [2, 1, 4],
+                [1, 3, 4],
+                [3, 0, 4],
+                [2, 0, 5],
+                [1, 2, 5],
+                [3, 1, 5],
+                [0, 3, 5],
+            ],
+        )
+    }
+
+    /// Two triangles meeting only at vertex 0.
+    fn bow_tie() -> HalfEdgeMesh<Vect<3, f64>> {
+        mesh(
+            &[
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [1., 1., 0.],
+                [-1., 0., 0.],
+                [-1., -1., 0.],
+            ],
+            &[[0, 1, 2], [0, 3, 4]],
+        )
+    }
+
+    /// Checks that twins pair up, every live vertex leads into a live face,
+    /// and no live face uses a removed vertex.
+    fn assert_consistent(mesh: &HalfEdgeMesh<Vect<3, f64>>) {
+        for h in mesh.half_edges() {
+            if let Some(t) = mesh.twin(h) {
+                assert!(mesh.contains_face(mesh.face(t)));
+                assert_eq!(mesh.twin(t), Some(h));
+                assert_eq!(mesh.origin(t), mesh.target(h));
+                assert_eq!(mesh.target(t), mesh.origin(h));
+            }
+            assert!(!mesh.removed_verts[mesh.origin(h).0]);
+        }
+        for v in mesh.vertices() {
+            if let Some(h) = mesh.outgoing(v) {
+                assert!(mesh.contains_face(mesh.face(h)));
+                assert_eq!(mesh.origin(h), v);
+            }
+        }
+    }
+
+    #[test]
+    fn flip_turns_the_diagonal() {
+        let mut mesh = square();
+        let h = mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(2))
+            .unwrap();
+        assert!(mesh.flip_edge(h));
+        assert_consistent(&mesh);
+        assert!(mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(2))
+            .is_none());
+        assert!(mesh
+            .find_half_edge(VertexHandle(1), VertexHandle(3))
+            .is_some());
+        assert_eq!(mesh.face_count(), 2);
+    }
+
+    #[test]
+    fn flip_needs_two_faces() {
+        let mut mesh = square();
+        let h = mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(1))
+            .unwrap();
+        assert!(!mesh.flip_edge(h));
+    }
+
+    #[test]
+    fn split_edge_on_both_sides() {
+        let mut mesh = square();
+        let h = mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(2))
+            .unwrap();
+        let m = mesh.split_edge(h, 0.5);
+        assert_consistent(&mesh);
+        assert_eq!(mesh.vert(m), Vect([0.5, 0.5, 0.]));
+        assert_eq!(mesh.face_count(), 4);
+        assert_eq!(mesh.valence(m), 4);
+        assert!(!mesh.is_boundary_vertex(m));
+    }
+
+    #[test]
+    fn split_boundary_edge() {
+        let mut mesh = square();
+        let h = mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(1))
+            .unwrap();
+        let m = mesh.split_edge(h, 0.5);
+        assert_consistent(&mesh);
+        assert_eq!(mesh.face_count(), 3);
+        assert!(mesh.is_boundary_vertex(m));
+        assert_eq!(mesh.into_mesh().boundary_loops().len(), 1);
+    }
+
+    #[test]
+    fn split_face_into_three() {
+        let mut mesh = tetrahedron();
+        let third = 1. / 3.;
+        mesh.split_face(FaceHandle(0), [third; 3]);
+        assert_consistent(&mesh);
+        assert_eq!(mesh.face_count(), 6);
+        assert!(mesh.into_mesh().is_watertight());
+    }
+
+    #[test]
+    fn collapse_keeps_a_closed_mesh_closed() {
+        let mut mesh = octahedron();
+        let h = mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(2))
+            .unwrap();
+        assert_eq!(mesh.collapse_edge(h, 0.5), Some(VertexHandle(0)));
+        assert_consistent(&mesh);
+        assert_eq!(mesh.vert_count(), 5);
+        assert_eq!(mesh.face_count(), 6);
+        assert_eq!(mesh.vert(VertexHandle(0)), Vect([0.5, 0.5, 0.]));
+        assert!(mesh.into_mesh().is_watertight());
+    }
+
+    #[test]
+    fn collapse_rejects_tetrahedron_edges() {
+        let mut mesh = tetrahedron();
+        let edges = mesh.half_edges().collect::<Vec<_>>();
+        for h in edges {
+            assert!(!mesh.can_collapse(h));
+            assert_eq!(mesh.collapse_edge(h, 0.5), None);
+        }
+        assert_eq!(mesh.face_count(), 4);
+    }
+
+    #[test]
+    fn collapse_undoes_a_face_split() {
+        let mut mesh = octahedron();
+        let third = 1. / 3.;
+        let p = mesh.split_face(FaceHandle(0), [third; 3]);
+        assert_eq!(mesh.valence(p), 3);
+        let h = mesh.find_half_edge(VertexHandle(0), p).unwrap();
+        assert_eq!(mesh.collapse_edge(h, 0.), Some(VertexHandle(0)));
+        assert_consistent(&mesh);
+        assert_eq!(mesh.face_count(), 8);
+        assert_eq!(mesh.vert(VertexHandle(0)), Vect([1., 0., 0.]));
+        assert!(mesh.into_mesh().is_watertight());
+    }
+
+    #[test]
+    fn collapse_rejects_interior_edge_between_boundaries() {
+        let mut mesh = square();
+        let h = mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(2))
+            .unwrap();
+        assert_eq!(mesh.collapse_edge(h, 0.5), None);
+    }
+
+    #[test]
+    fn collapse_boundary_edge() {
+        let mut mesh = mesh(
+            &[
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [2., 0., 0.],
+                [0.5, 1., 0.],
+                [1.5, 1., 0.],
+            ],
+            &[[0, 1, 3], [1, 4, 3], [1, 2, 4]],
+        );
+        let h = mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(1))
+            .unwrap();
+        assert_eq!(mesh.collapse_edge(h, 0.), Some(VertexHandle(0)));
+        assert_consistent(&mesh);
+        let mesh = mesh.into_mesh();
+        assert_eq!(mesh.tris.len(), 2);
+        assert_eq!(mesh.boundary_loops().len(), 1);
+    }
+
+    #[test]
+    fn collapse_rejects_bow_tie_centre() {
+        let mesh = bow_tie();
+        let into_centre = mesh
+            .find_half_edge(VertexHandle(2), VertexHandle(0))
+            .unwrap();
+        let out_of_centre = mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(1))
+            .unwrap();
+        assert!(!mesh.can_collapse(into_centre));
+        assert!(!mesh.can_collapse(out_of_centre));
+    }
+
+    #[test]
+    fn split_keeps_track_of_fans() {
+        let mut mesh = bow_tie();
+        let h = mesh
+            .find_half_edge(VertexHandle(0), VertexHandle(1))
+            .unwrap();
+        let m = mesh.split_edge(h, 0.5);
+        assert_consistent(&mesh);
+        assert!(mesh.multi_fan[0]);
+        assert!(!mesh.multi_fan[m.0]);
+        let into_centre = mesh
+            .find_half_edge(VertexHandle(2), VertexHandle(0))
+            .unwrap();
+        assert!(!mesh.can_collapse(into_centre));
+    }
+}
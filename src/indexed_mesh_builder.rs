@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use by_address::ByAddress;
+
+use crate::{
+    mesh_builder::{stitch_strip, StitchMode},
+    pieces::{MeshPatch, MeshTriPatch},
+    triangulate::triangulate,
+    vertex::VertexPosition,
+    Mesh,
+};
+
+/// A vertex added to an [`IndexedMeshBuilder`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct VertexId(usize);
+
+impl VertexId {
+    /// How many vertices were added before this one. [`IndexedMeshBuilder::finish`]
+    /// keeps vertices in this order but drops unused ones, so it's also the
+    /// index in the finished mesh as long as every earlier vertex is used.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A mesh builder that owns its vertices and refers to them by [`VertexId`].
+///
+/// Unlike [`MeshBuilder`](crate::mesh_builder::MeshBuilder), it doesn't borrow
+/// anything, so vertices can be added on the fly, their sources dropped early,
+/// and the builder sent to other threads.
+#[derive(Clone, Debug)]
+pub struct IndexedMeshBuilder<V: Copy> {
+    verts: Vec<V>,
+    tris: Vec<[usize; 3]>,
+}
+
+impl<V: Copy> Default for IndexedMeshBuilder<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Copy> IndexedMeshBuilder<V> {
+    pub fn new() -> Self {
+        Self {
+            verts: Vec::new(),
+            tris: Vec::new(),
+        }
+    }
+
+    pub fn add_vert(&mut self, vert: V) -> VertexId {
+        self.verts.push(vert);
+        VertexId(self.verts.len() - 1)
+    }
+
+    pub fn add_verts(&mut self, verts: impl IntoIterator<Item = V>) -> Vec<VertexId> {
+        verts.into_iter().map(|v| self.add_vert(v)).collect()
+    }
+
+    pub fn vert(&self, id: VertexId) -> &V {
+        &self.verts[id.0]
+    }
+
+    pub fn vert_mut(&mut self, id: VertexId) -> &mut V {
+        &mut self.verts[id.0]
+    }
+
+    pub fn vert_count(&self) -> usize {
+        self.verts.len()
+    }
+
+    pub fn tri_count(&self) -> usize {
+        self.tris.len()
+    }
+
+    pub fn add_tri(&mut self, tri: [VertexId; 3]) {
+        self.tris.push(tri.map(|v| v.0));
+    }
+
+    pub fn add_poly<const N: usize>(&mut self, poly: [VertexId; N]) {
+        for i in 1..N - 1 {
+            self.add_tri([poly[0], poly[i], poly[i + 1]]);
+        }
+    }
+
+    /// Splits the quad along its shorter diagonal.
+    pub fn add_quad(&mut self, quad: [VertexId; 4])
+    where
+        V: VertexPosition,
+    {
+        let pos = quad.map(|v| self.verts[v.0].pos());
+        let a = (pos[0] - pos[2]).sqr_magn();
+        let b = (pos[1] - pos[3]).sqr_magn();
+        if a < b {
+            self.add_tri([quad[0], quad[1], quad[2]]);
+            self.add_tri([quad[0], quad[2], quad[3]]);
+        } else {
+            self.add_tri([quad[1], quad[2], quad[3]]);
+            self.add_tri([quad[1], quad[3], quad[0]]);
+        }
+    }
+
+    /// Triangulates a planar polygon of any length, which may be concave, by
+    /// ear clipping. Unlike [`Self::add_poly`], it doesn't need a vertex that
+    /// can see all the others.
+    pub fn add_polygon(&mut self, poly: &[VertexId])
+    where
+        V: VertexPosition,
+        V::Scalar: PartialOrd,
+    {
+        self.add_polygon_with_holes(poly, &[])
+    }
+
+    /// Like [`Self::add_polygon`], but leaves out the `holes` inside `outer`.
+    /// Holes may wind either way; the triangles wind the same way as `outer`.
+    pub fn add_polygon_with_holes(&mut self, outer: &[VertexId], holes: &[&[VertexId]])
+    where
+        V: VertexPosition,
+        V::Scalar: PartialOrd,
+    {
+        for tri in triangulate(outer, holes, |v| self.verts[v.0].pos()) {
+            self.add_tri(tri);
+        }
+    }
+
+    pub fn patch<const N: usize>(&mut self, patch: impl IntoIterator<Item = [VertexId; N]>) {
+        for poly in patch {
+            self.add_poly(poly)
+        }
+    }
+
+    pub fn patch_quads(&mut self, patch: impl IntoIterator<Item = [VertexId; 4]>)
+    where
+        V: VertexPosition,
+    {
+        for quad in patch {
+            self.add_quad(quad)
+        }
+    }
+
+    pub fn stitch(
+        &mut self,
+        left: impl IntoIterator<Item = VertexId>,
+        right: impl IntoIterator<Item = VertexId>,
+    ) where
+        V: VertexPosition,
+    {
+        let (mut left, mut right) = (left.into_iter(), right.into_iter());
+        let (Some(mut l0), Some(mut r0)) = (left.next(), right.next()) else {
+            return;
+        };
+        for (l1, r1) in left.zip(right) {
+            self.add_quad([l0, r0, r1, l1]);
+            (l0, r0) = (l1, r1)
+        }
+    }
+
+    pub fn stitch_loop(
+        &mut self,
+        left: impl IntoIterator<Item = VertexId>,
+        right: impl IntoIterator<Item = VertexId>,
+    ) where
+        V: VertexPosition,
+    {
+        let (mut left, mut right) = (left.into_iter(), right.into_iter());
+        let (Some(l0), Some(r0)) = (left.next(), right.next()) else {
+            return;
+        };
+        let (mut l1, mut r1) = (l0, r0);
+        for (l2, r2) in left.zip(right) {
+            self.add_quad([l1, r1, r2, l2]);
+            (l1, r1) = (l2, r2)
+        }
+
+        self.add_quad([l1, r1, r0, l0]);
+    }
+
+    /// Like [`Self::stitch`], but joins seams with different numbers of
+    /// vertices without leaving any out.
+    pub fn stitch_uneven(
+        &mut self,
+        left: impl IntoIterator<Item = VertexId>,
+        right: impl IntoIterator<Item = VertexId>,
+        mode: StitchMode,
+    ) where
+        V: VertexPosition,
+        V::Scalar: PartialOrd,
+    {
+        let left = left.into_iter().collect::<Vec<_>>();
+        let right = right.into_iter().collect::<Vec<_>>();
+        if left.is_empty() || right.is_empty() {
+            return;
+        }
+        for tri in stitch_strip(&left, &right, |v| self.verts[v.0].pos(), mode) {
+            self.add_tri(tri);
+        }
+    }
+
+    /// Like [`Self::stitch_loop`], but for loops with different numbers of
+    /// vertices, joined starting from their first vertices.
+    pub fn stitch_loop_uneven(
+        &mut self,
+        left: impl IntoIterator<Item = VertexId>,
+        right: impl IntoIterator<Item = VertexId>,
+        mode: StitchMode,
+    ) where
+        V: VertexPosition,
+        V::Scalar: PartialOrd,
+    {
+        let mut left = left.into_iter().collect::<Vec<_>>();
+        let mut right = right.into_iter().collect::<Vec<_>>();
+        let (Some(&l0), Some(&r0)) = (left.first(), right.first()) else {
+            return;
+        };
+        left.push(l0);
+        right.push(r0);
+        for tri in stitch_strip(&left, &right, |v| self.verts[v.0].pos(), mode) {
+            self.add_tri(tri);
+        }
+    }
+
+    pub fn sinch_loop(&mut self, seam: impl IntoIterator<Item = VertexId>, knot: VertexId) {
+        let mut seam = seam.into_iter();
+        let Some(v0) = seam.next() else {
+            return;
+        };
+
+        let mut v1 = v0;
+        for v2 in seam {
+            self.add_poly([v1, v2, knot]);
+            v1 = v2;
+        }
+
+        self.add_poly([v1, v0, knot])
+    }
+
+    /// Copies in the vertices and triangles of `mesh`, returning the ids of its
+    /// vertices in order.
+    pub fn add_mesh(&mut self, mesh: &Mesh<V>) -> Vec<VertexId> {
+        let ids = self.add_verts(mesh.verts.iter().copied());
+        for tri in mesh.tris.iter() {
+            self.add_tri(tri.map(|i| ids[i]));
+        }
+        ids
+    }
+
+    /// Copies in the vertices of `grid`, returning their ids in the same
+    /// layout, along with a lookup from each original vertex's address.
+    fn add_grid<'v>(
+        &mut self,
+        grid: &'v [Vec<V>],
+    ) -> (Vec<Vec<VertexId>>, HashMap<ByAddress<&'v V>, VertexId>) {
+        let mut lookup = HashMap::new();
+        let ids = grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|v| {
+                        let id = self.add_vert(*v);
+                        lookup.insert(ByAddress(v), id);
+                        id
+                    })
+                    .collect()
+            })
+            .collect();
+        (ids, lookup)
+    }
+
+    /// Copies in the vertices and quads of `patch`, returning the ids of its
+    /// vertices row by row, so that its seams can be stitched after the patch
+    /// itself is gone.
+    pub fn add_patch(&mut self, patch: &MeshPatch<V>) -> Vec<Vec<VertexId>>
+    where
+        V: VertexPosition,
+    {
+        let (ids, lookup) = self.add_grid(&patch.verts);
+        for quad in patch {
+            self.add_quad(quad.map(|v| lookup[&ByAddress(v)]));
+        }
+        ids
+    }
+
+    /// Copies in the vertices and triangles of `patch`, returning the ids of
+    /// its vertices row by row.
+    pub fn add_tri_patch(&mut self, patch: &MeshTriPatch<V>) -> Vec<Vec<VertexId>> {
+        let (ids, lookup) = self.add_grid(&patch.verts);
+        for tri in patch {
+            self.add_tri(tri.map(|v| lookup[&ByAddress(v)]));
+        }
+        ids
+    }
+
+    /// Builds the mesh, leaving out vertices that no triangle uses. The rest
+    /// keep the order they were added in.
+    pub fn finish(self) -> Mesh<V> {
+        let mut used = vec![false; self.verts.len()];
+        for &i in self.tris.iter().flatten() {
+            used[i] = true;
+        }
+        let mut remap = vec![0; self.verts.len()];
+        let mut verts = Vec::with_capacity(self.verts.len());
+        for (i, vert) in self.verts.into_iter().enumerate() {
+            if used[i] {
+                remap[i] = verts.len();
+                verts.push(vert);
+            }
+        }
+        Mesh {
+            verts: verts.into_boxed_slice(),
+            tris: self.tris.iter().map(|tri| tri.map(|i| remap[i])).collect(),
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+extern crate self as mish_mesh;
+pub use ear_algae;
+pub use plyers;
+
 
 pub mod mesh_builder;
 pub mod pieces;
@@ -8,6 +12,26 @@ pub mod vertex;
 pub mod mesh;
 pub mod ply;
 pub mod merge_by_distance;
+pub mod half_edge;
+pub mod manifold;
+pub mod components;
+pub mod orientation;
+pub mod mass;
+pub mod bvh;
+pub mod distance;
+pub mod winding;
+pub mod subdivision;
+pub mod decimate;
+pub mod remesh;
+pub mod smooth;
+pub mod curvature;
+pub mod tangent;
+pub mod normals;
+pub mod indexed_mesh_builder;
+pub mod poly_mesh;
+mod union_find;
+mod scalar;
+mod triangulate;
 
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
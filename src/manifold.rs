@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use crate::{union_find::UnionFind, Mesh};
+
+impl<V: Copy> Mesh<V> {
+    /// Maps every undirected edge, keyed with the lower index first, to the
+    /// triangles that use it.
+    pub(crate) fn edge_tris(&self) -> HashMap<[usize; 2], Vec<usize>> {
+        let mut edges: HashMap<[usize; 2], Vec<usize>> = HashMap::new();
+        for (t, tri) in self.tris.iter().enumerate() {
+            for i in 0..3 {
+                let (a, b) = (tri[i], tri[(i + 1) % 3]);
+                edges.entry([a.min(b), a.max(b)]).or_default().push(t);
+            }
+        }
+        edges
+    }
+
+    /// The directed edges that belong to exactly one triangle, in that
+    /// triangle's winding.
+    pub fn boundary_edges(&self) -> Vec<[usize; 2]> {
+        let mut edges = self
+            .edge_tris()
+            .into_iter()
+            .filter(|(_, tris)| tris.len() == 1)
+            .map(|([a, b], tris)| {
+                let tri = self.tris[tris[0]];
+                if (0..3).any(|i| tri[i] == a && tri[(i + 1) % 3] == b) {
+                    [a, b]
+                } else {
+                    [b, a]
+                }
+            })
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// The vertices that lie on an open boundary, in ascending order.
+    pub fn boundary_verts(&self) -> Vec<usize> {
+        let mut verts = self.boundary_edges().concat();
+        verts.sort_unstable();
+        verts.dedup();
+        verts
+    }
+
+    /// The open boundaries of the mesh as closed cycles of vertex indices,
+    /// following the winding of the triangles along them.
+    ///
+    /// A vertex where several boundaries touch appears once in each loop
+    /// through it. Boundary edges that don't close into a loop, as happens
+    /// around inconsistently wound or non-manifold triangles, are left out;
+    /// [`Self::boundary_edges`] still lists them.
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let edges = self.boundary_edges();
+        let index = edges
+            .iter()
+            .enumerate()
+            .map(|(i, &e)| (e, i))
+            .collect::<HashMap<_, _>>();
+        let mut half_edges = HashMap::new();
+        for (t, tri) in self.tris.iter().enumerate() {
+            for i in 0..3 {
+                half_edges.insert([tri[i], tri[(i + 1) % 3]], t);
+            }
+        }
+
+        // The boundary edge that follows `a -> b`, found by turning around the
+        // fan of triangles at `b` until it opens onto the boundary again.
+        let next = |[a, b]: [usize; 2]| -> Option<usize> {
+            let mut t = half_edges[&[a, b]];
+            for _ in 0..self.tris.len() {
+                let tri = self.tris[t];
+                let i = (0..3).find(|&i| tri[i] == b)?;
+                let c = tri[(i + 1) % 3];
+                if let Some(&e) = index.get(&[b, c]) {
+                    return Some(e);
+                }
+                t = *half_edges.get(&[c, b])?;
+            }
+            None
+        };
+        let next = edges.iter().map(|&e| next(e)).collect::<Vec<_>>();
+
+        let mut done = vec![false; edges.len()];
+        let mut visited_from = vec![usize::MAX; edges.len()];
+        let mut loops = Vec::new();
+        for start in 0..edges.len() {
+            if done[start] {
+                continue;
+            }
+            let mut path = vec![start];
+            let mut e = start;
+            visited_from[e] = start;
+            let closed = loop {
+                match next[e] {
+                    Some(n) if n == start => break true,
+                    Some(n) if !done[n] && visited_from[n] != start => {
+                        path.push(n);
+                        visited_from[n] = start;
+                        e = n;
+                    }
+                    _ => break false,
+                }
+            };
+            if closed {
+                for &e in &path {
+                    done[e] = true;
+                }
+                loops.push(path.iter().map(|&e| edges[e][0]).collect());
+            } else {
+                done[start] = true;
+            }
+        }
+        loops
+    }
+
+    /// Edges shared by three or more triangles.
+    pub fn non_manifold_edges(&self) -> Vec<[usize; 2]> {
+        let mut edges = self
+            .edge_tris()
+            .into_iter()
+            .filter(|(_, tris)| tris.len() > 2)
+            .map(|(edge, _)| edge)
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// Vertices whose triangles form more than one fan, such as the centre of
+    /// a bow-tie.
+    pub fn non_manifold_verts(&self) -> Vec<usize> {
+        let mut nodes: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut links = Vec::new();
+        for tri in self.tris.iter() {
+            for i in 0..3 {
+                let v = tri[i];
+                let mut node = |w| {
+                    let len = nodes.len();
+                    *nodes.entry((v, w)).or_insert(len)
+                };
+                links.push((node(tri[(i + 1) % 3]), node(tri[(i + 2) % 3])));
+            }
+        }
+
+        let mut fans = UnionFind::new(nodes.len());
+        for (a, b) in links {
+            fans.union(a, b);
+        }
+
+        let mut roots: HashMap<usize, usize> = HashMap::new();
+        let mut verts = Vec::new();
+        for (&(v, _), &node) in nodes.iter() {
+            let root = fans.find(node);
+            if *roots.entry(v).or_insert(root) != root {
+                verts.push(v);
+            }
+        }
+        verts.sort_unstable();
+        verts.dedup();
+        verts
+    }
+
+    /// Whether every edge is shared by exactly two triangles and every vertex
+    /// has a single fan.
+    pub fn is_watertight(&self) -> bool {
+        self.edge_tris().values().all(|tris| tris.len() == 2)
+            && self.non_manifold_verts().is_empty()
+    }
+}
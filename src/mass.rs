@@ -0,0 +1,111 @@
+use ear_algae::{prelude::*, traits::Ring};
+
+use crate::{scalar::int, vertex::VertexPosition, Mesh};
+
+impl<V: VertexPosition> Mesh<V> {
+    pub fn tri_areas(&self) -> Vec<V::Scalar> {
+        self.tri_verts()
+            .into_iter()
+            .map(|t| {
+                let [a, b, c] = t.map_out(V::pos);
+                (b - a).cross(c - a).magn() / int(2)
+            })
+            .collect()
+    }
+
+    pub fn surface_area(&self) -> V::Scalar {
+        self.tri_areas()
+            .into_iter()
+            .fold(V::Scalar::ZERO, |acc, a| acc + a)
+    }
+
+    /// The volume enclosed by the mesh, positive when the triangles wind
+    /// counter-clockwise seen from outside. Only meaningful for closed meshes.
+    pub fn signed_volume(&self) -> V::Scalar {
+        self.tri_verts()
+            .into_iter()
+            .map(|t| {
+                let [a, b, c] = t.map_out(V::pos);
+                a.dot(b.cross(c))
+            })
+            .fold(V::Scalar::ZERO, |acc, v| acc + v)
+            / int(6)
+    }
+
+    /// The area-weighted centre of the surface, or `None` if it has no area.
+    pub fn surface_centroid(&self) -> Option<Vect<3, V::Scalar>>
+    where
+        V::Scalar: PartialEq,
+    {
+        let (area, moment) = self.tri_verts().into_iter().fold(
+            (V::Scalar::ZERO, Vect::ZERO),
+            |(area, moment), t| {
+                let [a, b, c] = t.map_out(V::pos);
+                let tri_area = (b - a).cross(c - a).magn();
+                (area + tri_area, moment + (a + b + c) * tri_area)
+            },
+        );
+        (area != V::Scalar::ZERO).then(|| moment * (V::Scalar::ONE / (area * int(3))))
+    }
+
+    /// The centre of mass of the solid bounded by the mesh, or `None` if it
+    /// encloses no volume.
+    pub fn centroid(&self) -> Option<Vect<3, V::Scalar>>
+    where
+        V::Scalar: PartialEq,
+    {
+        let (volume, moment) = self.tri_verts().into_iter().fold(
+            (V::Scalar::ZERO, Vect::ZERO),
+            |(volume, moment), t| {
+                let [a, b, c] = t.map_out(V::pos);
+                let det = a.dot(b.cross(c));
+                (volume + det, moment + (a + b + c) * det)
+            },
+        );
+        (volume != V::Scalar::ZERO).then(|| moment * (V::Scalar::ONE / (volume * int(4))))
+    }
+
+    /// The inertia tensor of the solid bounded by the mesh at unit density,
+    /// taken about its centre of mass. `None` if it encloses no volume.
+    pub fn inertia_tensor(&self) -> Option<[[V::Scalar; 3]; 3]>
+    where
+        V::Scalar: PartialEq,
+    {
+        let zero = V::Scalar::ZERO;
+        let mut covariance = [[zero; 3]; 3];
+        for t in self.tri_verts() {
+            let [a, b, c] = t.map_out(V::pos);
+            let det = a.dot(b.cross(c));
+            let s = a + b + c;
+            // Integral of x xᵀ over the tetrahedron (0, a, b, c), scaled by 120.
+            for i in 0..3 {
+                for j in 0..3 {
+                    let term = a[i] * a[j] + b[i] * b[j] + c[i] * c[j] + s[i] * s[j];
+                    covariance[i][j] = covariance[i][j] + det * term;
+                }
+            }
+        }
+
+        let volume = self.signed_volume();
+        let centroid = self.centroid()?;
+        let scale = V::Scalar::ONE / int(120);
+        for i in 0..3 {
+            for j in 0..3 {
+                covariance[i][j] = covariance[i][j] * scale - volume * centroid[i] * centroid[j];
+            }
+        }
+
+        let trace = covariance[0][0] + covariance[1][1] + covariance[2][2];
+        let mut inertia = [[zero; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                inertia[i][j] = if i == j {
+                    trace - covariance[i][j]
+                } else {
+                    -covariance[i][j]
+                };
+            }
+        }
+        Some(inertia)
+    }
+}
@@ -2,11 +2,79 @@
 
 use std::collections::HashMap;
 
-use crate::vertex::VertexPosition;
+use ear_algae::{traits::Field, Vect};
+
+use crate::{triangulate::triangulate, vertex::VertexPosition};
 
 use super::Mesh;
 
 use by_address::ByAddress;
+use rayon::prelude::*;
+
+/// How [`MeshBuilder::stitch_uneven`] walks two seams of different lengths.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum StitchMode {
+    /// Always add the shorter of the two possible next diagonals.
+    #[default]
+    ShortestDiagonal,
+    /// Advance along each seam in proportion to its arc length, so that
+    /// vertices pair up with those at the same fraction of the other seam.
+    ArcLength,
+}
+
+/// Triangulates the strip between two polylines, each visited once from start
+/// to end. Triangles wind like the quads of [`MeshBuilder::stitch`].
+pub(crate) fn stitch_strip<T: Copy, S: Field + PartialOrd>(
+    left: &[T],
+    right: &[T],
+    pos: impl Fn(T) -> Vect<3, S>,
+    mode: StitchMode,
+) -> Vec<[T; 3]> {
+    let arc = |seam: &[T]| {
+        let mut total = S::ZERO;
+        let mut lengths = vec![total];
+        for pair in seam.windows(2) {
+            total = total + (pos(pair[1]) - pos(pair[0])).magn();
+            lengths.push(total);
+        }
+        lengths
+    };
+    let (left_arc, right_arc) = match mode {
+        StitchMode::ArcLength => (arc(left), arc(right)),
+        StitchMode::ShortestDiagonal => (Vec::new(), Vec::new()),
+    };
+
+    let mut tris = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i + 1 < left.len() || j + 1 < right.len() {
+        let advance_left = if i + 1 == left.len() {
+            false
+        } else if j + 1 == right.len() {
+            true
+        } else {
+            match mode {
+                StitchMode::ShortestDiagonal => {
+                    let via_left = (pos(left[i + 1]) - pos(right[j])).sqr_magn();
+                    let via_right = (pos(left[i]) - pos(right[j + 1])).sqr_magn();
+                    via_left <= via_right
+                }
+                StitchMode::ArcLength => {
+                    // Compares the fractions of each seam covered, without dividing.
+                    let (left_total, right_total) = (left_arc[left.len() - 1], right_arc[right.len() - 1]);
+                    left_arc[i + 1] * right_total <= right_arc[j + 1] * left_total
+                }
+            }
+        };
+        if advance_left {
+            tris.push([left[i], right[j], left[i + 1]]);
+            i += 1;
+        } else {
+            tris.push([left[i], right[j], right[j + 1]]);
+            j += 1;
+        }
+    }
+    tris
+}
 
 #[derive(Default)]
 pub struct MeshBuilder<'v, V: Copy> {
@@ -40,6 +108,19 @@ impl<'v, V: Copy> MeshBuilder<'v, V> {
         }
     }
 
+    /// Triangulates a planar polygon of any length, which may be concave, by ear
+    /// clipping. Unlike [`Self::add_poly`], it doesn't need a vertex that can see
+    /// all the others.
+    pub fn add_polygon(&mut self, poly: &[&'v V]) where V: VertexPosition, V::Scalar: PartialOrd {
+        self.add_polygon_with_holes(poly, &[])
+    }
+
+    /// Like [`Self::add_polygon`], but leaves out the `holes` inside `outer`.
+    /// Holes may wind either way; the triangles wind the same way as `outer`.
+    pub fn add_polygon_with_holes(&mut self, outer: &[&'v V], holes: &[&[&'v V]]) where V: VertexPosition, V::Scalar: PartialOrd {
+        self.tris.extend(triangulate(outer, holes, |v| v.pos()))
+    }
+
     pub fn patch<const N: usize>(&mut self, patch: impl IntoIterator<Item = [&'v V; N]> ) {
         for poly in patch {
             self.add_poly(poly)
@@ -74,6 +155,31 @@ impl<'v, V: Copy> MeshBuilder<'v, V> {
         self.add_quad([l1, r1, r0, l0]);
     }
 
+    /// Like [`Self::stitch`], but joins seams with different numbers of
+    /// vertices without leaving any out. The triangles fan out from whichever
+    /// seam has fewer vertices where `mode` calls for it.
+    pub fn stitch_uneven(&mut self, left: impl Iterator<Item = &'v V>, right: impl Iterator<Item = &'v V>, mode: StitchMode) where V: VertexPosition, V::Scalar: PartialOrd {
+        let left = left.collect::<Vec<_>>();
+        let right = right.collect::<Vec<_>>();
+        if left.is_empty() || right.is_empty() {
+            return;
+        }
+        self.tris.extend(stitch_strip(&left, &right, |v| v.pos(), mode));
+    }
+
+    /// Like [`Self::stitch_loop`], but for loops with different numbers of
+    /// vertices. The loops are joined starting from their first vertices.
+    pub fn stitch_loop_uneven(&mut self, left: impl Iterator<Item = &'v V>, right: impl Iterator<Item = &'v V>, mode: StitchMode) where V: VertexPosition, V::Scalar: PartialOrd {
+        let mut left = left.collect::<Vec<_>>();
+        let mut right = right.collect::<Vec<_>>();
+        let (Some(&l0), Some(&r0)) = (left.first(), right.first()) else {
+            return;
+        };
+        left.push(l0);
+        right.push(r0);
+        self.tris.extend(stitch_strip(&left, &right, |v| v.pos(), mode));
+    }
+
     pub fn sinch_loop(&mut self, mut seam: impl Iterator<Item = &'v V>, knot: &'v V) {
         let v0 = if let Some(v) = seam.next() {v} else {return};
         
@@ -195,7 +301,58 @@ impl<'v, V: Copy> MeshBuilder<'v, V> {
         Mesh {verts, tris}
     }
 
+    /// Concatenates the triangles of several builders, in order. Together with
+    /// [`Self::finish_par`], this lets pieces of a model be built on separate
+    /// threads and joined into one mesh, sharing the vertices of their seams.
+    pub fn merge(builders: impl IntoParallelIterator<Item = Self>) -> Self where V: Sync {
+        MeshBuilder {
+            tris: builders.into_par_iter().flat_map_iter(|b| b.tris).collect()
+        }
+    }
 
-}
+    /// Like [`Self::finish`], but in parallel. Vertices are deduplicated by
+    /// address and numbered in order of first use, exactly as `finish` does.
+    pub fn finish_par(self) -> super::Mesh<V> where V: Send + Sync {
+        let corners: Vec<ByAddress<&'v V>> = self.tris.par_iter()
+            .flat_map_iter(|&tri| tri.map(ByAddress))
+            .collect();
+
+        let mut sorted: Vec<(ByAddress<&'v V>, usize)> = corners.par_iter()
+            .copied()
+            .enumerate()
+            .map(|(i, v)| (v, i))
+            .collect();
+        sorted.par_sort_unstable();
+
+        // The first corner of each distinct vertex, in address order.
+        let unique: Vec<(ByAddress<&'v V>, usize)> = (0..sorted.len()).into_par_iter()
+            .filter(|&k| k == 0 || sorted[k].0 != sorted[k - 1].0)
+            .map(|k| sorted[k])
+            .collect();
+
+        let mut by_first_use: Vec<(usize, usize)> = unique.par_iter()
+            .enumerate()
+            .map(|(rank, &(_, first))| (first, rank))
+            .collect();
+        by_first_use.par_sort_unstable();
+
+        let mut index_of_rank = vec![0; unique.len()];
+        for (i, &(_, rank)) in by_first_use.iter().enumerate() {
+            index_of_rank[rank] = i;
+        }
 
+        let verts = by_first_use.par_iter()
+            .map(|&(first, _)| *corners[first].0)
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+        let tris = corners.par_chunks(3)
+            .map(|tri| [0, 1, 2].map(|i| {
+                let rank = unique.partition_point(|(v, _)| *v < tri[i]);
+                index_of_rank[rank]
+            }))
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
 
+        Mesh {verts, tris}
+    }
+}
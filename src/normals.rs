@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use ear_algae::{prelude::*, traits::Ring};
+
+use rayon::prelude::*;
+
+use crate::{
+    scalar::angle_between,
+    union_find::UnionFind,
+    vertex::{VertexNormal, VertexPosition},
+    Mesh,
+};
+
+/// How much each face counts towards the normals of its vertices.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum NormalWeighting {
+    /// Every face counts the same.
+    Uniform,
+    /// Faces count by their area.
+    Area,
+    /// Faces count by their interior angle at the vertex.
+    #[default]
+    Angle,
+    /// Nelson Max's weights: the sine of the angle at the vertex over the
+    /// lengths of the two edges meeting there. Exact for vertices on a sphere.
+    Max,
+}
+
+impl<V: VertexPosition + VertexNormal> Mesh<V> {
+    /// The weighted normal each corner of `tri` contributes to its vertex, or
+    /// zeroes if the triangle is degenerate.
+    fn corner_normals(
+        &self,
+        tri: [usize; 3],
+        weighting: NormalWeighting,
+    ) -> [Vect<3, V::Scalar>; 3] {
+        let [a, b, c] = tri.map(|i| self.verts[i].pos());
+        let cross = (b - a).cross(c - a);
+        let Some(normal) = cross.normal() else {
+            return [Vect::ZERO; 3];
+        };
+        let normal = Vect::from(normal);
+        [0, 1, 2].map(|i| {
+            let p = self.verts[tri[i]].pos();
+            let e0 = self.verts[tri[(i + 1) % 3]].pos() - p;
+            let e1 = self.verts[tri[(i + 2) % 3]].pos() - p;
+            match weighting {
+                NormalWeighting::Uniform => normal,
+                NormalWeighting::Area => cross,
+                NormalWeighting::Angle => {
+                    normal * angle_between(e0.normal_or_zero(), e1.normal_or_zero())
+                }
+                NormalWeighting::Max => {
+                    e0.cross(e1) * (V::Scalar::ONE / (e0.sqr_magn() * e1.sqr_magn()))
+                }
+            }
+        })
+    }
+
+    /// The corners (as `3 * tri + i`) around each vertex, in triangle order.
+    fn vertex_corners(&self) -> Vec<Vec<usize>> {
+        let mut corners = vec![Vec::new(); self.verts.len()];
+        for (t, tri) in self.tris.iter().enumerate() {
+            for (i, &v) in tri.iter().enumerate() {
+                corners[v].push(3 * t + i);
+            }
+        }
+        corners
+    }
+
+    fn sum_corners(corners: &[usize], normals: &[[Vect<3, V::Scalar>; 3]]) -> Vect<3, V::Scalar> {
+        corners
+            .iter()
+            .fold(Vect::ZERO, |acc, &c| acc + normals[c / 3][c % 3])
+            .normal_or_zero()
+    }
+
+    /// Computes a unit normal for every vertex from the faces around it.
+    /// Vertices without any non-degenerate face get zero.
+    pub fn vertex_normals_sync(&self, weighting: NormalWeighting) -> Vec<Vect<3, V::Scalar>> {
+        let normals = self
+            .tris
+            .iter()
+            .map(|&tri| self.corner_normals(tri, weighting))
+            .collect::<Vec<_>>();
+        self.vertex_corners()
+            .iter()
+            .map(|corners| Self::sum_corners(corners, &normals))
+            .collect()
+    }
+
+    /// Like [`Self::vertex_normals_sync`], but in parallel. Each vertex sums its
+    /// faces in the same order either way, so the results are bit-identical.
+    pub fn vertex_normals(&self, weighting: NormalWeighting) -> Vec<Vect<3, V::Scalar>>
+    where
+        V: Send + Sync,
+        V::Scalar: Send + Sync,
+    {
+        let normals = self
+            .tris
+            .par_iter()
+            .map(|&tri| self.corner_normals(tri, weighting))
+            .collect::<Vec<_>>();
+        self.vertex_corners()
+            .par_iter()
+            .map(|corners| Self::sum_corners(corners, &normals))
+            .collect()
+    }
+
+    /// Fills in every zero normal from the faces around its vertex, and
+    /// normalizes the rest.
+    pub fn autocomplete_normals_weighted_sync(self, weighting: NormalWeighting) -> Self {
+        let normals = self.vertex_normals_sync(weighting);
+        let Mesh { mut verts, tris } = self;
+        for (vert, normal) in verts.iter_mut().zip(normals) {
+            complete_normal(vert, normal);
+        }
+        Mesh { verts, tris }
+    }
+
+    /// Like [`Self::autocomplete_normals_weighted_sync`], but in parallel, with
+    /// bit-identical results.
+    pub fn autocomplete_normals_weighted(self, weighting: NormalWeighting) -> Self
+    where
+        V: Send + Sync,
+        V::Scalar: Send + Sync,
+    {
+        let normals = self.vertex_normals(weighting);
+        let Mesh { mut verts, tris } = self;
+        verts
+            .par_iter_mut()
+            .zip(normals)
+            .for_each(|(vert, normal)| complete_normal(vert, normal));
+        Mesh { verts, tris }
+    }
+
+    fn face_normals(&self) -> Vec<Vect<3, V::Scalar>> {
+        self.tris
+            .iter()
+            .map(|tri| {
+                let [a, b, c] = tri.map(|i| self.verts[i].pos());
+                (b - a).cross(c - a).normal_or_zero()
+            })
+            .collect()
+    }
+
+    /// The interior angle of `tri` at its `i`th corner.
+    fn corner_angle(&self, tri: [usize; 3], i: usize) -> V::Scalar {
+        let p = self.verts[tri[i]].pos();
+        let e0 = (self.verts[tri[(i + 1) % 3]].pos() - p).normal_or_zero();
+        let e1 = (self.verts[tri[(i + 2) % 3]].pos() - p).normal_or_zero();
+        angle_between(e0, e1)
+    }
+
+    /// Gives every triangle its own three vertices, all carrying the face
+    /// normal, so that the mesh renders faceted. Vertices that no triangle uses
+    /// are dropped.
+    pub fn with_flat_normals(self) -> Self {
+        let normals = self.face_normals();
+        let verts = self
+            .tris
+            .iter()
+            .zip(normals)
+            .flat_map(|(tri, normal)| {
+                tri.map(|i| {
+                    let mut vert = self.verts[i];
+                    vert.set_normal(normal);
+                    vert
+                })
+            })
+            .collect::<Box<[_]>>();
+        let tris = (0..self.tris.len())
+            .map(|t| [3 * t, 3 * t + 1, 3 * t + 2])
+            .collect();
+        Mesh { verts, tris }
+    }
+}
+
+impl<V: VertexPosition + VertexNormal> Mesh<V>
+where
+    V::Scalar: PartialOrd,
+{
+    /// Recomputes the normals, smoothing across edges whose faces meet at less
+    /// than `crease_angle` (in radians) and leaving the rest as hard edges.
+    ///
+    /// A vertex is duplicated once for each smooth fan of faces around it, so
+    /// a cube comes out with three copies of each corner and a cylinder keeps
+    /// its sides smooth but its caps sharp. Vertices that no triangle uses are
+    /// dropped.
+    pub fn with_crease_normals(self, crease_angle: V::Scalar) -> Self {
+        let normals = self.face_normals();
+        let corner =
+            |t: usize, v: usize| 3 * t + self.tris[t].iter().position(|&i| i == v).unwrap_or(0);
+
+        let mut fans = UnionFind::new(3 * self.tris.len());
+        for ([a, b], tris) in self.edge_tris() {
+            for (k, &t) in tris.iter().enumerate() {
+                for &u in &tris[k + 1..] {
+                    let degenerate = normals[t] == Vect::ZERO || normals[u] == Vect::ZERO;
+                    if degenerate || angle_between(normals[t], normals[u]) <= crease_angle {
+                        fans.union(corner(t, a), corner(u, a));
+                        fans.union(corner(t, b), corner(u, b));
+                    }
+                }
+            }
+        }
+
+        let mut sums = vec![Vect::ZERO; 3 * self.tris.len()];
+        for (t, tri) in self.tris.iter().enumerate() {
+            for i in 0..3 {
+                let root = fans.find(3 * t + i);
+                sums[root] = sums[root] + normals[t] * self.corner_angle(*tri, i);
+            }
+        }
+
+        let mut verts = Vec::with_capacity(self.verts.len());
+        let mut index = HashMap::new();
+        let tris = self
+            .tris
+            .iter()
+            .enumerate()
+            .map(|(t, tri)| {
+                let mut tri = *tri;
+                for (i, v) in tri.iter_mut().enumerate() {
+                    let root = fans.find(3 * t + i);
+                    *v = *index.entry(root).or_insert_with(|| {
+                        let mut vert = self.verts[*v];
+                        vert.set_normal(sums[root].normal_or_zero());
+                        verts.push(vert);
+                        verts.len() - 1
+                    });
+                }
+                tri
+            })
+            .collect();
+
+        Mesh {
+            verts: verts.into_boxed_slice(),
+            tris,
+        }
+    }
+}
+
+fn complete_normal<V: VertexNormal>(vert: &mut V, computed: Vect<3, V::Scalar>) {
+    let normal = vert.normal();
+    if normal == Vect::ZERO {
+        vert.set_normal(computed);
+    } else {
+        vert.set_normal(normal.normal_or_zero());
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+use ear_algae::traits::Ring;
+
+use crate::{vertex::VertexPosition, Mesh};
+
+fn traverses(tri: [usize; 3], a: usize, b: usize) -> bool {
+    (0..3).any(|i| tri[i] == a && tri[(i + 1) % 3] == b)
+}
+
+impl<V: Copy> Mesh<V> {
+    /// Edges whose two triangles both run along them in the same direction.
+    pub fn inconsistent_edges(&self) -> Vec<[usize; 2]> {
+        let mut edges = self
+            .edge_tris()
+            .into_iter()
+            .filter(|(_, tris)| tris.len() == 2)
+            .filter(|([a, b], tris)| {
+                traverses(self.tris[tris[0]], *a, *b) == traverses(self.tris[tris[1]], *a, *b)
+            })
+            .map(|(edge, _)| edge)
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges
+    }
+
+    pub fn flip_tris(&mut self, tris: impl IntoIterator<Item = usize>) {
+        for t in tris {
+            self.tris[t].swap(1, 2);
+        }
+    }
+}
+
+impl<V: VertexPosition> Mesh<V>
+where
+    V::Scalar: PartialOrd,
+{
+    /// Rewinds triangles so that each edge-connected component is consistently
+    /// oriented, then turns closed components outward by their signed volume.
+    ///
+    /// Returns the mesh along with the indices of the triangles that were flipped.
+    /// Components that cannot be oriented, such as a Möbius strip, keep the
+    /// orientation they are first reached with.
+    pub fn orient(mut self) -> (Self, Vec<usize>) {
+        let edge_tris = self.edge_tris();
+        let mut neighbours: Vec<Vec<(usize, [usize; 2])>> = vec![Vec::new(); self.tris.len()];
+        let mut open = vec![false; self.tris.len()];
+        for (&edge, tris) in edge_tris.iter() {
+            if let &[s, t] = tris.as_slice() {
+                neighbours[s].push((t, edge));
+                neighbours[t].push((s, edge));
+            } else {
+                tris.iter().for_each(|&t| open[t] = true);
+            }
+        }
+
+        let mut flip: Vec<Option<bool>> = vec![None; self.tris.len()];
+        let mut queue = VecDeque::new();
+        for seed in 0..self.tris.len() {
+            if flip[seed].is_some() {
+                continue;
+            }
+
+            flip[seed] = Some(false);
+            queue.push_back(seed);
+            let mut component = Vec::new();
+            let mut closed = true;
+
+            while let Some(t) = queue.pop_front() {
+                component.push(t);
+                closed &= !open[t];
+                let t_flip = flip[t] == Some(true);
+                for &(u, [a, b]) in neighbours[t].iter() {
+                    if flip[u].is_none() {
+                        let t_forward = traverses(self.tris[t], a, b) != t_flip;
+                        flip[u] = Some(traverses(self.tris[u], a, b) == t_forward);
+                        queue.push_back(u);
+                    }
+                }
+            }
+
+            if closed {
+                let volume = component
+                    .iter()
+                    .map(|&t| {
+                        let [a, b, c] = self.tris[t].map(|i| self.verts[i].pos());
+                        let volume = a.dot(b.cross(c));
+                        if flip[t] == Some(true) {
+                            -volume
+                        } else {
+                            volume
+                        }
+                    })
+                    .fold(V::Scalar::ZERO, |acc, v| acc + v);
+                if volume < V::Scalar::ZERO {
+                    for t in component {
+                        flip[t] = flip[t].map(|f| !f);
+                    }
+                }
+            }
+        }
+
+        let flipped = (0..self.tris.len())
+            .filter(|&t| flip[t] == Some(true))
+            .collect::<Vec<_>>();
+        self.flip_tris(flipped.iter().copied());
+        (self, flipped)
+    }
+}
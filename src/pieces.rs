@@ -3,6 +3,8 @@ use std::{fmt::Debug, ops::Index};
 use ear_algae::{ops::Aplable, prelude::*};
 use index_from_end::{IntoIndex, Len};
 
+use crate::vertex::VertexUv;
+
 mod iter;
 
 #[derive(Copy, Clone, Debug)]
@@ -118,7 +120,7 @@ macro_rules! umap {
 
 #[derive(Clone)]
 pub struct MeshPatch<V: Copy> {
-    verts: Vec<Vec<V>>,
+    pub(crate) verts: Vec<Vec<V>>,
     len_u: usize,
     len_v: usize,
     start_offset_u: isize,
@@ -171,6 +173,20 @@ impl<V: Copy> MeshPatch<V> {
         }
     }
 
+    /// Like [`MeshPatch::make`], but also stores each vertex's `(u, v)` as its
+    /// texture coordinates.
+    pub fn make_uv(u_map: UMap, v_map: UMap, f: impl Fn(f32, f32) -> V) -> Self
+    where
+        V: VertexUv,
+        V::Scalar: From<f32>,
+    {
+        Self::make(u_map, v_map, |u, v| {
+            let mut vert = f(u, v);
+            vert.set_uv(Vect([u.into(), v.into()]));
+            vert
+        })
+    }
+
     pub fn u_start<'a>(
         &'a self,
     ) -> VirtualMeshLinearSegment<'a, V, impl Fn(usize) -> &'a V + Clone> {
@@ -266,7 +282,7 @@ where
 
 #[derive(Clone)]
 pub struct MeshTriPatch<V: Copy> {
-    verts: Vec<Vec<V>>,
+    pub(crate) verts: Vec<Vec<V>>,
 }
 
 impl<V: Copy> MeshTriPatch<V> {
@@ -285,6 +301,20 @@ impl<V: Copy> MeshTriPatch<V> {
         Self { verts }
     }
 
+    /// Like [`MeshTriPatch::make_polar`], but also stores each vertex's
+    /// `(r, theta)` as its texture coordinates.
+    pub fn make_polar_uv(r_map: UMap, theta_map: UMap, f: impl Fn(f32, f32) -> V) -> Self
+    where
+        V: VertexUv,
+        V::Scalar: From<f32>,
+    {
+        Self::make_polar(r_map, theta_map, |r, theta| {
+            let mut vert = f(r, theta);
+            vert.set_uv(Vect([r.into(), theta.into()]));
+            vert
+        })
+    }
+
     pub fn r_start(&self) -> impl DoubleEndedIterator<Item = &V> + Clone {
         self.verts[0].iter()
     }
@@ -1,49 +1,66 @@
-use ear_algae::{traits::Ring, Vect};
+use ear_algae::{
+    traits::{Field, Ring},
+    Vect,
+};
 use plyers::ply::*;
 use properties::{PlyPropList, PlyPropery};
 
-use crate::Mesh;
+use crate::{
+    poly_mesh::PolyMesh,
+    vertex::{ColorChannel, PosColor, PosNormalColor},
+    Mesh,
+};
 
 pub mod properties;
 
+pub use mish_mesh_derive::PlyVertex;
+
 impl<V: Copy + PlyVertex> Mesh<V> {
     pub fn into_ply<E: PlyElementSet>(
         self,
         encoding: Encoding,
     ) -> Result<Ply<E>, ConsistencyError> {
-        let mut ply = Ply::new();
-        ply.header.encoding = encoding;
-        ply.header.elements.add(V::element_def("vertex".to_owned()));
-        ply.header.elements.add({
-            let mut def = ElementDef::new("face".to_owned());
-            def.properties.add(PropertyDef::new(
-                "vertex_index".to_owned(),
-                PlyPropList::<u8, u32>::PLY_PROP_TYPE,
-            ));
-            def
-        });
-        let verts = self
-            .verts()
-            .iter()
-            .copied()
-            .map(PlyVertex::into_ply_element)
-            .collect();
-        ply.payload.insert("vertex".to_owned(), verts);
         let faces = self
             .tris_iter()
-            .map(|&t| {
-                let mut element = E::new();
-                element.set_property(
-                    "vertex_index".to_owned(),
-                    PlyPropList::<u8, _>::from(t.map(|x| x as u32)).into_prop(),
-                );
-                element
-            })
+            .map(|t| t.map(|x| x as u32).to_vec())
             .collect();
-        ply.payload.insert("face".to_owned(), faces);
+        write_ply(
+            self.verts(),
+            faces,
+            PlyPropList::<u8, u32>::PLY_PROP_TYPE,
+            encoding,
+        )
+    }
+
+    /// Reads a mesh of triangles, or returns `None` if any face has some other
+    /// number of vertices. Read those with [`PolyMesh::from_ply`] and then
+    /// [`PolyMesh::triangulate`] them.
+    pub fn from_ply<E: PlyElementGet>(ply: Ply<E>) -> Option<Self> {
+        let mesh = PolyMesh::from_ply(ply)?;
+        if mesh.faces().any(|face| face.len() != 3) {
+            return None;
+        }
+        Some(mesh.fan_triangulate())
+    }
+}
 
-        ply.make_consistent()?;
-        Ok(ply)
+impl<V: Copy + PlyVertex> PolyMesh<V> {
+    /// Writes the faces as `vertex_index` lists with a `uchar` count, or a
+    /// `uint` count if any face has more than 255 vertices.
+    pub fn into_ply<E: PlyElementSet>(
+        self,
+        encoding: Encoding,
+    ) -> Result<Ply<E>, ConsistencyError> {
+        let list_type = if self.faces().all(|face| face.len() <= u8::MAX as usize) {
+            PlyPropList::<u8, u32>::PLY_PROP_TYPE
+        } else {
+            PlyPropList::<u32, u32>::PLY_PROP_TYPE
+        };
+        let faces = self
+            .faces()
+            .map(|face| face.iter().map(|&x| x as u32).collect())
+            .collect();
+        write_ply(self.verts(), faces, list_type, encoding)
     }
 
     pub fn from_ply<E: PlyElementGet>(ply: Ply<E>) -> Option<Self> {
@@ -52,27 +69,62 @@ impl<V: Copy + PlyVertex> Mesh<V> {
             .get("vertex")?
             .iter()
             .map(|v| V::from_ply_element(v))
-            .collect::<Option<Vec<_>>>()?
-            .into_boxed_slice();
+            .collect::<Option<Vec<_>>>()?;
 
-        let tris = ply
+        let faces = ply
             .payload
             .get("face")?
             .iter()
-            .map(|e| -> Option<_> {
-                let list = PlyPropList::<u8, u32>::from_element_prop(e, "vertex_index")?.list;
-                if list.len() != 3 {
-                    return None;
-                }
-                Some([list[0] as usize, list[1] as usize, list[2] as usize])
-            })
-            .collect::<Option<Vec<_>>>()?
-            .into_boxed_slice();
-
-        Some(Mesh { verts, tris })
+            .map(|e| PlyPropList::<u8, u32>::from_element_prop(e, "vertex_index"))
+            .collect::<Option<Vec<_>>>()?;
+
+        PolyMesh::new(
+            verts,
+            faces
+                .into_iter()
+                .map(|face| face.list.into_iter().map(|x| x as usize)),
+        )
     }
 }
 
+fn write_ply<V: Copy + PlyVertex, E: PlyElementSet>(
+    verts: &[V],
+    faces: Vec<Vec<u32>>,
+    list_type: PropertyType,
+    encoding: Encoding,
+) -> Result<Ply<E>, ConsistencyError> {
+    let mut ply = Ply::new();
+    ply.header.encoding = encoding;
+    ply.header.elements.add(V::element_def("vertex".to_owned()));
+    ply.header.elements.add({
+        let mut def = ElementDef::new("face".to_owned());
+        def.properties
+            .add(PropertyDef::new("vertex_index".to_owned(), list_type));
+        def
+    });
+    let verts = verts
+        .iter()
+        .copied()
+        .map(PlyVertex::into_ply_element)
+        .collect();
+    ply.payload.insert("vertex".to_owned(), verts);
+    let faces = faces
+        .into_iter()
+        .map(|face| {
+            let mut element = E::new();
+            element.set_property(
+                "vertex_index".to_owned(),
+                PlyPropList::<u32, _>::from(face).into_prop(),
+            );
+            element
+        })
+        .collect();
+    ply.payload.insert("face".to_owned(), faces);
+
+    ply.make_consistent()?;
+    Ok(ply)
+}
+
 pub trait PlyVertex: Sized {
     fn element_def(name: String) -> ElementDef;
     fn into_ply_element<E: PlyElementSet>(self) -> E;
@@ -107,3 +159,92 @@ impl<S: Ring + PlyPropery> PlyVertex for Vect<3, S> {
         Some(Vect([x, y, z]))
     }
 }
+
+const POSITION: [&str; 3] = ["x", "y", "z"];
+const NORMAL: [&str; 3] = ["nx", "ny", "nz"];
+const COLOR: [&str; 4] = ["red", "green", "blue", "alpha"];
+
+fn add_props<P: PlyPropery>(def: &mut ElementDef, names: &[&str]) {
+    for name in names {
+        def.properties
+            .add(PropertyDef::new((*name).to_owned(), P::PLY_PROP_TYPE));
+    }
+}
+
+fn set_props<E: PlyElementSet, P: PlyPropery, const N: usize>(
+    element: &mut E,
+    names: [&str; N],
+    values: [P; N],
+) {
+    for (name, value) in names.into_iter().zip(values) {
+        element.set_property(name.to_owned(), value.into_prop());
+    }
+}
+
+fn get_props<E: PlyElementGet, P: PlyPropery, const N: usize>(
+    element: &E,
+    names: [&str; N],
+) -> Option<[P; N]> {
+    names
+        .iter()
+        .map(|name| P::from_element_prop(element, name))
+        .collect::<Option<Vec<_>>>()?
+        .try_into()
+        .ok()
+}
+
+/// Reads `red`, `green`, `blue` and, if present, `alpha`.
+fn get_color<E: PlyElementGet, C: ColorChannel + PlyPropery>(element: &E) -> Option<[C; 4]> {
+    let [r, g, b] = get_props(element, ["red", "green", "blue"])?;
+    let a = C::from_element_prop(element, "alpha").unwrap_or(C::MAX);
+    Some([r, g, b, a])
+}
+
+impl<S: Field + PlyPropery, C: ColorChannel + PlyPropery> PlyVertex for PosColor<S, C> {
+    fn element_def(name: String) -> ElementDef {
+        let mut def = ElementDef::new(name);
+        add_props::<S>(&mut def, &POSITION);
+        add_props::<C>(&mut def, &COLOR);
+        def
+    }
+
+    fn into_ply_element<E: PlyElementSet>(self) -> E {
+        let mut vert = E::new();
+        set_props(&mut vert, POSITION, self.pos.0);
+        set_props(&mut vert, COLOR, self.color);
+        vert
+    }
+
+    fn from_ply_element<E: PlyElementGet>(element: &E) -> Option<Self> {
+        Some(Self {
+            pos: Vect(get_props(element, POSITION)?),
+            color: get_color(element)?,
+        })
+    }
+}
+
+impl<S: Field + PlyPropery, C: ColorChannel + PlyPropery> PlyVertex for PosNormalColor<S, C> {
+    fn element_def(name: String) -> ElementDef {
+        let mut def = ElementDef::new(name);
+        add_props::<S>(&mut def, &POSITION);
+        add_props::<S>(&mut def, &NORMAL);
+        add_props::<C>(&mut def, &COLOR);
+        def
+    }
+
+    fn into_ply_element<E: PlyElementSet>(self) -> E {
+        let mut vert = E::new();
+        set_props(&mut vert, POSITION, self.pos.0);
+        set_props(&mut vert, NORMAL, self.normal.0);
+        set_props(&mut vert, COLOR, self.color);
+        vert
+    }
+
+    fn from_ply_element<E: PlyElementGet>(element: &E) -> Option<Self> {
+        Some(Self {
+            pos: Vect(get_props(element, POSITION)?),
+            normal: Vect(get_props(element, NORMAL)?),
+            color: get_color(element)?,
+        })
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{triangulate::triangulate, vertex::VertexPosition, Mesh};
+
+/// How [`PolyMesh::triangulate`] splits faces with more than three vertices.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Triangulation {
+    /// Fan out from each face's first vertex, like
+    /// [`MeshBuilder::add_poly`](crate::mesh_builder::MeshBuilder::add_poly).
+    /// Only right for convex faces.
+    Fan,
+    /// Split quads along their shorter diagonal, like
+    /// [`MeshBuilder::add_quad`](crate::mesh_builder::MeshBuilder::add_quad),
+    /// and fan out larger faces.
+    ShortestDiagonal,
+    /// Ear clip every face in its best-fit plane, which also handles concave
+    /// faces.
+    #[default]
+    EarClip,
+}
+
+/// A mesh whose faces may have any number of vertices, so that quads and
+/// n-gons survive until they're triangulated.
+///
+/// The faces are stored back to back in one index buffer, with `offsets[f]`
+/// the start of face `f` and a final offset at the end of the buffer.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PolyMesh<V: Copy> {
+    verts: Box<[V]>,
+    indices: Box<[usize]>,
+    offsets: Box<[usize]>,
+}
+
+impl<V: Copy> PolyMesh<V> {
+    /// Gathers `faces` into a mesh, or returns `None` if any face has fewer
+    /// than three vertices or refers to a vertex that doesn't exist.
+    pub fn new<F: IntoIterator<Item = usize>>(
+        verts: impl Into<Box<[V]>>,
+        faces: impl IntoIterator<Item = F>,
+    ) -> Option<Self> {
+        let verts = verts.into();
+        let mut indices = Vec::new();
+        let mut offsets = vec![0];
+        for face in faces {
+            indices.extend(face);
+            let start = *offsets.last()?;
+            if indices.len() - start < 3 || indices[start..].iter().any(|&i| i >= verts.len()) {
+                return None;
+            }
+            offsets.push(indices.len());
+        }
+        Some(Self {
+            verts,
+            indices: indices.into_boxed_slice(),
+            offsets: offsets.into_boxed_slice(),
+        })
+    }
+
+    pub fn verts(&self) -> &[V] {
+        &self.verts
+    }
+
+    pub fn verts_mut(&mut self) -> &mut [V] {
+        &mut self.verts
+    }
+
+    pub fn face_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// The vertex indices of face `f`, in winding order.
+    pub fn face(&self, f: usize) -> &[usize] {
+        &self.indices[self.offsets[f]..self.offsets[f + 1]]
+    }
+
+    pub fn faces(&self) -> impl Iterator<Item = &[usize]> + '_ {
+        self.offsets.windows(2).map(|w| &self.indices[w[0]..w[1]])
+    }
+
+    /// The whole index buffer, which [`Self::offsets`] splits into faces.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    pub fn map<T: Copy>(&self, f: impl Fn(V) -> T) -> PolyMesh<T> {
+        PolyMesh {
+            verts: self.verts.iter().map(|&v| f(v)).collect(),
+            indices: self.indices.clone(),
+            offsets: self.offsets.clone(),
+        }
+    }
+
+    /// Triangulates every face by fanning out from its first vertex, which
+    /// needs nothing from the vertices but is only right for convex faces.
+    pub fn fan_triangulate(&self) -> Mesh<V> {
+        let tris = self
+            .faces()
+            .flat_map(|face| (1..face.len() - 1).map(move |i| [face[0], face[i], face[i + 1]]))
+            .collect();
+        Mesh {
+            verts: self.verts.clone(),
+            tris,
+        }
+    }
+
+    /// Triangulates every face as `mode` says. Triangles wind the same way as
+    /// the faces they come from, and the vertices are kept as they are.
+    pub fn triangulate(&self, mode: Triangulation) -> Mesh<V>
+    where
+        V: VertexPosition,
+        V::Scalar: PartialOrd,
+    {
+        let pos = |i: usize| self.verts[i].pos();
+        let mut tris = Vec::with_capacity(self.indices.len() - 2 * self.face_count());
+        for face in self.faces() {
+            match (mode, face) {
+                (Triangulation::ShortestDiagonal, &[a, b, c, d]) => {
+                    if (pos(a) - pos(c)).sqr_magn() < (pos(b) - pos(d)).sqr_magn() {
+                        tris.extend([[a, b, c], [a, c, d]]);
+                    } else {
+                        tris.extend([[b, c, d], [b, d, a]]);
+                    }
+                }
+                (Triangulation::EarClip, face) if face.len() > 3 => {
+                    tris.extend(triangulate(face, &[], pos));
+                }
+                _ => tris.extend((1..face.len() - 1).map(|i| [face[0], face[i], face[i + 1]])),
+            }
+        }
+        Mesh {
+            verts: self.verts.clone(),
+            tris: tris.into_boxed_slice(),
+        }
+    }
+}
+
+impl<V: Copy> From<Mesh<V>> for PolyMesh<V> {
+    fn from(mesh: Mesh<V>) -> Self {
+        Self {
+            verts: mesh.verts,
+            indices: mesh.tris.iter().flatten().copied().collect(),
+            offsets: (0..=mesh.tris.len()).map(|t| 3 * t).collect(),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use ear_algae::{prelude::*, traits::Ring};
+
+use crate::{
+    half_edge::{HalfEdgeHandle, HalfEdgeMesh, VertexHandle},
+    scalar::int,
+    vertex::{VertexBlend, VertexPosition},
+    Mesh,
+};
+
+fn sqr_length<V: VertexPosition>(mesh: &HalfEdgeMesh<V>, h: HalfEdgeHandle) -> V::Scalar {
+    (mesh.vert(mesh.target(h)).pos() - mesh.vert(mesh.origin(h)).pos()).sqr_magn()
+}
+
+fn live_half_edges<V: Copy>(mesh: &HalfEdgeMesh<V>) -> Vec<HalfEdgeHandle> {
+    mesh.half_edges().collect()
+}
+
+fn is_live<V: Copy>(mesh: &HalfEdgeMesh<V>, h: HalfEdgeHandle) -> bool {
+    mesh.contains_face(mesh.face(h))
+}
+
+/// The ends of every edge. Splits and flips renumber half-edges, so passes
+/// that make them work through these and look each edge up again.
+fn edge_ends<V: Copy>(mesh: &HalfEdgeMesh<V>) -> Vec<[VertexHandle; 2]> {
+    mesh.edges()
+        .map(|e| [mesh.origin(e.0), mesh.target(e.0)])
+        .collect()
+}
+
+fn find_edge<V: Copy>(mesh: &HalfEdgeMesh<V>, [a, b]: [VertexHandle; 2]) -> Option<HalfEdgeHandle> {
+    mesh.find_half_edge(a, b)
+        .or_else(|| mesh.find_half_edge(b, a))
+}
+
+impl<V: VertexPosition + VertexBlend> Mesh<V>
+where
+    V::Scalar: PartialOrd,
+{
+    /// Remeshes towards edges of `target_length` by repeatedly splitting long
+    /// edges, collapsing short ones, flipping towards regular valence and
+    /// relaxing vertices along the surface, then projecting them back onto the
+    /// original mesh.
+    ///
+    /// Boundary vertices are never moved or removed, though boundary edges may
+    /// be split. Unless `target_length` is positive, the mesh is returned as it
+    /// is.
+    pub fn remesh_isotropic(self, target_length: V::Scalar, iterations: usize) -> Self {
+        if target_length.partial_cmp(&V::Scalar::ZERO) != Some(Ordering::Greater) {
+            return self;
+        }
+        let surface = self.bvh();
+        let high = target_length * int(4) / int(3);
+        let low = target_length * int(4) / int(5);
+        let (high, low) = (high * high, low * low);
+        let mut mesh = HalfEdgeMesh::from(self);
+
+        for _ in 0..iterations {
+            split_long_edges(&mut mesh, high);
+            collapse_short_edges(&mut mesh, low, high);
+            equalize_valences(&mut mesh);
+            relax_tangentially(&mut mesh);
+
+            let interior = mesh
+                .vertices()
+                .filter(|&v| !mesh.is_boundary_vertex(v))
+                .collect::<Vec<_>>();
+            for v in interior {
+                if let Some(closest) = surface.closest_point(mesh.vert(v).pos()) {
+                    mesh.verts_mut()[v.0].set_pos(closest.point);
+                }
+            }
+        }
+
+        mesh.into_mesh()
+    }
+}
+
+/// Each pass halves the long edges, so this many passes cover edges up to
+/// 2^32 times the target length.
+const MAX_SPLIT_PASSES: usize = 32;
+
+/// Edges without a twin that more than one face uses are left alone, since
+/// splitting one face would crack the others.
+fn split_long_edges<V: VertexPosition + VertexBlend>(mesh: &mut HalfEdgeMesh<V>, high: V::Scalar)
+where
+    V::Scalar: PartialOrd,
+{
+    let mut faces_at = HashMap::<[VertexHandle; 2], usize>::new();
+    for h in mesh.half_edges() {
+        let (a, b) = (mesh.origin(h), mesh.target(h));
+        *faces_at.entry([a.min(b), a.max(b)]).or_default() += 1;
+    }
+
+    for _ in 0..MAX_SPLIT_PASSES {
+        let mut split = false;
+        for ends @ [a, b] in edge_ends(mesh) {
+            let Some(h) = find_edge(mesh, ends) else {
+                continue;
+            };
+            let shared = faces_at.get(&[a.min(b), a.max(b)]).is_some_and(|&n| n > 1);
+            let non_manifold = mesh.twin(h).is_none() && shared;
+            if !non_manifold && sqr_length(mesh, h) > high {
+                mesh.split_edge(h, V::Scalar::ONE / int(2));
+                split = true;
+            }
+        }
+        if !split {
+            break;
+        }
+    }
+}
+
+fn collapse_short_edges<V: VertexPosition + VertexBlend>(
+    mesh: &mut HalfEdgeMesh<V>,
+    low: V::Scalar,
+    high: V::Scalar,
+) where
+    V::Scalar: PartialOrd,
+{
+    for h in live_half_edges(mesh) {
+        if !is_live(mesh, h) || sqr_length(mesh, h) >= low {
+            continue;
+        }
+        let (a, b) = (mesh.origin(h), mesh.target(h));
+        if mesh.is_boundary_vertex(b) {
+            continue;
+        }
+        let a_pos = mesh.vert(a).pos();
+        let stays_short = mesh
+            .vertex_neighbours(b)
+            .all(|w| (mesh.vert(w).pos() - a_pos).sqr_magn() <= high);
+        if stays_short {
+            mesh.collapse_edge(h, V::Scalar::ZERO);
+        }
+    }
+}
+
+fn valence_target<V: Copy>(mesh: &HalfEdgeMesh<V>, v: VertexHandle) -> isize {
+    if mesh.is_boundary_vertex(v) {
+        4
+    } else {
+        6
+    }
+}
+
+fn equalize_valences<V: Copy>(mesh: &mut HalfEdgeMesh<V>) {
+    for ends in edge_ends(mesh) {
+        let Some(h) = find_edge(mesh, ends) else {
+            continue;
+        };
+        let Some(t) = mesh.twin(h) else {
+            continue;
+        };
+        let verts = [
+            mesh.origin(h),
+            mesh.target(h),
+            mesh.origin(mesh.prev(h)),
+            mesh.origin(mesh.prev(t)),
+        ];
+        let deviation = |change: [isize; 4]| -> isize {
+            (0..4)
+                .map(|i| {
+                    let valence = mesh.valence(verts[i]) as isize + change[i];
+                    (valence - valence_target(mesh, verts[i])).abs()
+                })
+                .sum()
+        };
+        if deviation([-1, -1, 1, 1]) < deviation([0; 4]) {
+            mesh.flip_edge(h);
+        }
+    }
+}
+
+fn relax_tangentially<V: VertexPosition>(mesh: &mut HalfEdgeMesh<V>) {
+    let moves = mesh
+        .vertices()
+        .filter(|&v| !mesh.is_boundary_vertex(v))
+        .filter_map(|v| {
+            let neighbours = mesh.vertex_neighbours(v).collect::<Vec<_>>();
+            if neighbours.is_empty() {
+                return None;
+            }
+            let centroid = neighbours
+                .iter()
+                .fold(Vect::ZERO, |acc, &w| acc + mesh.vert(w).pos())
+                * (V::Scalar::ONE / int(neighbours.len()));
+
+            let normal = mesh
+                .vertex_faces(v)
+                .map(|f| {
+                    let [a, b, c] = mesh.face_verts(f).map(|v| mesh.vert(v).pos());
+                    (b - a).cross(c - a)
+                })
+                .fold(Vect::ZERO, |acc, n| acc + n)
+                .normal_or_zero();
+
+            let p = mesh.vert(v).pos();
+            Some((v, centroid + normal * normal.dot(p - centroid)))
+        })
+        .collect::<Vec<_>>();
+
+    for (v, p) in moves {
+        mesh.verts_mut()[v.0].set_pos(p);
+    }
+}
@@ -0,0 +1,46 @@
+use ear_algae::{
+    prelude::*,
+    traits::{Field, Ring},
+};
+
+/// Builds a small integer constant in a generic scalar type.
+pub(crate) fn int<S: Ring>(n: usize) -> S {
+    (0..n).fold(S::ZERO, |acc, _| acc + S::ONE)
+}
+
+/// The angle between two unit vectors (or zero if both are zero).
+///
+/// Rounding can push `a · b` just past ±1, where `acos` gives NaN. Instead
+/// this takes twice the `acos` of `|a + b| / sqrt(|a + b|² + |a - b|²)`, a
+/// ratio that can't round past 1.
+pub(crate) fn angle_between<S: Field>(a: Vect<3, S>, b: Vect<3, S>) -> S {
+    let (sum, diff) = ((a + b).sqr_magn(), (a - b).sqr_magn());
+    let total = sum + diff;
+    if total == S::ZERO {
+        return S::ZERO;
+    }
+    let half = (sum / total).sqrt().acos();
+    half + half
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use ear_algae::{prelude::*, Vect};
+
+    use super::angle_between;
+
+    #[test]
+    fn angle_between_survives_rounding() {
+        let x = 1. / 3f64.sqrt();
+        let a = Vect([x, x, x]);
+        let b = Vect([-x, -x, -x]);
+        assert!(a.dot(a) > 1.);
+        assert!(a.dot(b) < -1.);
+        assert_eq!(angle_between(a, a), 0.);
+        assert!((angle_between(a, b) - PI).abs() < 1e-12);
+        let right = angle_between(Vect([1., 0., 0.]), Vect([0., 1., 0.]));
+        assert!((right - FRAC_PI_2).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,187 @@
+use ear_algae::{prelude::*, traits::Ring};
+
+use crate::{scalar::int, vertex::VertexPosition, Mesh};
+
+/// How neighbours are weighted when averaging around a vertex.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum LaplacianWeights {
+    #[default]
+    Uniform,
+    /// Cotangent weights, which follow the surface geometry rather than its
+    /// tessellation. Negative weights from obtuse angles are dropped.
+    Cotangent,
+}
+
+impl<V: VertexPosition> Mesh<V>
+where
+    V::Scalar: PartialOrd,
+{
+    fn pin_mask(&self, pinned: &[usize]) -> Vec<bool> {
+        let mut mask = vec![false; self.verts.len()];
+        pinned.iter().for_each(|&v| mask[v] = true);
+        mask
+    }
+
+    fn positions(&self) -> Vec<Vect<3, V::Scalar>> {
+        self.verts.iter().map(|v| v.pos()).collect()
+    }
+
+    /// The offset from each vertex to the weighted average of its neighbours.
+    fn laplacian(
+        &self,
+        pos: &[Vect<3, V::Scalar>],
+        weights: LaplacianWeights,
+    ) -> Vec<Vect<3, V::Scalar>> {
+        let zero = V::Scalar::ZERO;
+        let mut sum = vec![Vect::ZERO; pos.len()];
+        let mut total = vec![zero; pos.len()];
+        let mut add = |j: usize, k: usize, w: V::Scalar| {
+            sum[j] = sum[j] + (pos[k] - pos[j]) * w;
+            sum[k] = sum[k] + (pos[j] - pos[k]) * w;
+            total[j] = total[j] + w;
+            total[k] = total[k] + w;
+        };
+
+        match weights {
+            LaplacianWeights::Uniform => {
+                for [j, k] in self.edge_tris().into_keys() {
+                    add(j, k, V::Scalar::ONE);
+                }
+            }
+            LaplacianWeights::Cotangent => {
+                for tri in self.tris.iter() {
+                    for i in 0..3 {
+                        let (j, k) = (tri[(i + 1) % 3], tri[(i + 2) % 3]);
+                        let e0 = pos[j] - pos[tri[i]];
+                        let e1 = pos[k] - pos[tri[i]];
+                        let sin = e0.cross(e1).magn();
+                        if sin > zero {
+                            let cot = e0.dot(e1) / sin;
+                            if cot > zero {
+                                add(j, k, cot / int(2));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        sum.into_iter()
+            .zip(total)
+            .map(|(s, t)| {
+                if t > zero {
+                    s * (V::Scalar::ONE / t)
+                } else {
+                    Vect::ZERO
+                }
+            })
+            .collect()
+    }
+
+    fn set_positions(&mut self, pos: &[Vect<3, V::Scalar>]) {
+        for (v, &p) in self.verts.iter_mut().zip(pos) {
+            v.set_pos(p);
+        }
+    }
+
+    /// Moves each vertex `lambda` of the way towards the average of its
+    /// neighbours, `iterations` times. Vertices in `pinned` stay put; pass
+    /// [`Mesh::boundary_verts`] to hold the boundary.
+    pub fn smooth_laplacian(
+        mut self,
+        weights: LaplacianWeights,
+        lambda: V::Scalar,
+        iterations: usize,
+        pinned: &[usize],
+    ) -> Self {
+        let pinned = self.pin_mask(pinned);
+        let mut pos = self.positions();
+        for _ in 0..iterations {
+            self.step(&mut pos, weights, lambda, &pinned);
+        }
+        self.set_positions(&pos);
+        self
+    }
+
+    fn step(
+        &self,
+        pos: &mut [Vect<3, V::Scalar>],
+        weights: LaplacianWeights,
+        factor: V::Scalar,
+        pinned: &[bool],
+    ) {
+        let laplacian = self.laplacian(pos, weights);
+        for (i, l) in laplacian.into_iter().enumerate() {
+            if !pinned[i] {
+                pos[i] = pos[i] + l * factor;
+            }
+        }
+    }
+
+    /// Taubin λ|μ smoothing: each iteration smooths by `lambda` and then
+    /// inflates by the negative `mu`, which keeps the mesh from shrinking.
+    /// `mu` should be negative and slightly larger in magnitude than `lambda`.
+    pub fn smooth_taubin(
+        mut self,
+        weights: LaplacianWeights,
+        lambda: V::Scalar,
+        mu: V::Scalar,
+        iterations: usize,
+        pinned: &[usize],
+    ) -> Self {
+        let pinned = self.pin_mask(pinned);
+        let mut pos = self.positions();
+        for _ in 0..iterations {
+            self.step(&mut pos, weights, lambda, &pinned);
+            self.step(&mut pos, weights, mu, &pinned);
+        }
+        self.set_positions(&pos);
+        self
+    }
+
+    /// Humphrey's Classes smoothing, which pushes each Laplacian step back
+    /// towards both the original and the previous positions. `alpha` weighs the
+    /// original positions and `beta` the vertex's own correction against its
+    /// neighbours'.
+    pub fn smooth_hc(
+        mut self,
+        alpha: V::Scalar,
+        beta: V::Scalar,
+        iterations: usize,
+        pinned: &[usize],
+    ) -> Self {
+        let one = V::Scalar::ONE;
+        let pinned = self.pin_mask(pinned);
+        let original = self.positions();
+        let mut neighbours = vec![Vec::new(); original.len()];
+        for [j, k] in self.edge_tris().into_keys() {
+            neighbours[j].push(k);
+            neighbours[k].push(j);
+        }
+
+        let mut pos = original.clone();
+        for _ in 0..iterations {
+            let previous = pos.clone();
+            let laplacian = self.laplacian(&previous, LaplacianWeights::Uniform);
+            let mut push = Vec::with_capacity(pos.len());
+            for i in 0..pos.len() {
+                if !pinned[i] {
+                    pos[i] = previous[i] + laplacian[i];
+                }
+                push.push(pos[i] - (original[i] * alpha + previous[i] * (one - alpha)));
+            }
+            for i in 0..pos.len() {
+                if pinned[i] || neighbours[i].is_empty() {
+                    continue;
+                }
+                let around = neighbours[i]
+                    .iter()
+                    .fold(Vect::ZERO, |acc, &j| acc + push[j])
+                    * ((one - beta) / int(neighbours[i].len()));
+                pos[i] = pos[i] - (push[i] * beta + around);
+            }
+        }
+        self.set_positions(&pos);
+        self
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet};
+
+use ear_algae::traits::Ring;
+
+use crate::{scalar::int, vertex::VertexBlend, Mesh};
+
+impl<V: VertexBlend> Mesh<V> {
+    /// One step of Loop subdivision, splitting every triangle into four.
+    ///
+    /// Boundary edges, edges shared by more than two triangles and edges of
+    /// degenerate triangles are kept sharp.
+    pub fn subdivide_loop(&self) -> Self {
+        self.subdivide_loop_with_creases(&[])
+    }
+
+    /// One step of Loop subdivision that also keeps the given edges sharp.
+    /// Crease edges are given by their two vertex indices, in either order.
+    pub fn subdivide_loop_with_creases(&self, creases: &[[usize; 2]]) -> Self {
+        let creases = creases
+            .iter()
+            .map(|&[a, b]| [a.min(b), a.max(b)])
+            .collect::<HashSet<_>>();
+        let edge_tris = self.edge_tris();
+        // A degenerate triangle such as `[a, a, b]` lists itself twice for the
+        // edge `[a, b]`, which mustn't read as a smooth edge between two faces.
+        let is_sharp = |edge: &[usize; 2], tris: &[usize]| {
+            tris.len() != 2 || tris[0] == tris[1] || creases.contains(edge)
+        };
+
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); self.verts.len()];
+        let mut sharp_neighbours: Vec<Vec<usize>> = vec![Vec::new(); self.verts.len()];
+        for (edge @ [a, b], tris) in edge_tris.iter() {
+            neighbours[*a].push(*b);
+            neighbours[*b].push(*a);
+            if is_sharp(edge, tris) {
+                sharp_neighbours[*a].push(*b);
+                sharp_neighbours[*b].push(*a);
+            }
+        }
+
+        let mut verts = self
+            .verts
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| match sharp_neighbours[i].as_slice() {
+                &[a, b] => V::blend(&[
+                    (v, int::<V::Scalar>(3) / int(4)),
+                    (self.verts[a], V::Scalar::ONE / int(8)),
+                    (self.verts[b], V::Scalar::ONE / int(8)),
+                ]),
+                sharp if sharp.len() > 2 => v,
+                _ => {
+                    let n = neighbours[i].len();
+                    if n == 0 {
+                        return v;
+                    }
+                    let beta = if n == 3 {
+                        int::<V::Scalar>(3) / int(16)
+                    } else {
+                        int::<V::Scalar>(3) / int(8 * n)
+                    };
+                    let mut weighted = vec![(v, V::Scalar::ONE - beta * int(n))];
+                    weighted.extend(neighbours[i].iter().map(|&j| (self.verts[j], beta)));
+                    V::blend(&weighted)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut edge_verts = HashMap::<[usize; 2], usize>::new();
+        for (edge @ [a, b], tris) in edge_tris.iter() {
+            let (a, b) = (self.verts[*a], self.verts[*b]);
+            let vert = if is_sharp(edge, tris) {
+                let half = V::Scalar::ONE / int(2);
+                V::blend(&[(a, half), (b, half)])
+            } else {
+                let [c, d] = [tris[0], tris[1]].map(|t| {
+                    let third = self.tris[t]
+                        .into_iter()
+                        .find(|i| !edge.contains(i))
+                        .unwrap(); // Two distinct triangles each have a corner off the edge.
+                    self.verts[third]
+                });
+                let near = int::<V::Scalar>(3) / int(8);
+                let far = V::Scalar::ONE / int(8);
+                V::blend(&[(a, near), (b, near), (c, far), (d, far)])
+            };
+            edge_verts.insert(*edge, verts.len());
+            verts.push(vert);
+        }
+
+        let edge_vert = |a: usize, b: usize| edge_verts[&[a.min(b), a.max(b)]];
+        let tris = self
+            .tris
+            .iter()
+            .flat_map(|&[a, b, c]| {
+                let (ab, bc, ca) = (edge_vert(a, b), edge_vert(b, c), edge_vert(c, a));
+                [[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]
+            })
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+
+        Mesh {
+            verts: verts.into_boxed_slice(),
+            tris,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ear_algae::{prelude::*, Vect};
+
+    use crate::{vertex::PosNormal, Mesh};
+
+    #[test]
+    fn subdivides_normals_with_positions() {
+        let corners = [
+            [1., 0., 0.],
+            [-1., 0., 0.],
+            [0., 1., 0.],
+            [0., -1., 0.],
+            [0., 0., 1.],
+            [0., 0., -1.],
+        ];
+        let octahedron = Mesh {
+            verts: corners
+                .map(|p| PosNormal {
+                    pos: Vect(p),
+                    normal: Vect(p),
+                })
+                .into(),
+            tris: [
+                [0, 2, 4],
+                [2, 1, 4],
+                [1, 3, 4],
+                [3, 0, 4],
+                [2, 0, 5],
+                [1, 2, 5],
+                [3, 1, 5],
+                [0, 3, 5],
+            ]
+            .into(),
+        };
+
+        // Each normal starts out equal to its position, so after blending and
+        // renormalizing it should point along the blended position.
+        let mesh = octahedron.subdivide_loop();
+        assert_eq!(mesh.tris_iter().len(), 32);
+        assert_eq!(mesh.verts().len(), 18);
+        for vert in mesh.verts() {
+            assert!((vert.normal.magn() - 1.).abs() < 1e-12);
+            assert!((vert.normal - vert.pos.normal_or_zero()).magn() < 1e-12);
+        }
+    }
+}
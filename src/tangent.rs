@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+
+use ear_algae::{prelude::*, traits::Ring};
+
+use crate::{
+    scalar::angle_between,
+    union_find::UnionFind,
+    vertex::{VertexNormal, VertexPosition, VertexTangent, VertexUv},
+    Mesh,
+};
+
+struct FaceFrame<S> {
+    /// The unit direction of increasing u, or zero on degenerate faces.
+    tangent: Vect<3, S>,
+    /// Whether the texture is mapped without mirroring.
+    orientation: bool,
+    /// Whether the face has area both in space and in texture space.
+    contributes: bool,
+}
+
+impl<V> Mesh<V>
+where
+    V: VertexPosition + VertexNormal + VertexUv + VertexTangent,
+    V::Scalar: PartialOrd,
+{
+    /// Generates per-vertex tangents following MikkTSpace, so that normal maps
+    /// baked by Blender and other MikkTSpace tools render as intended.
+    ///
+    /// Corners of a vertex are grouped over shared edges whose faces agree on
+    /// texture orientation. A vertex used by several such groups, for example
+    /// on a mirrored UV seam, is split so that each group gets its own tangent
+    /// frame.
+    ///
+    /// The tangent's `w` holds the bitangent sign, as in glTF: the bitangent is
+    /// `normal.cross(tangent) * w`.
+    pub fn generate_tangents(self) -> Self {
+        let zero = V::Scalar::ZERO;
+        let one = V::Scalar::ONE;
+        let mut frames = self
+            .tris
+            .iter()
+            .map(|tri| {
+                let [p0, p1, p2] = tri.map(|i| self.verts[i].pos());
+                let [t0, t1, t2] = tri.map(|i| self.verts[i].uv());
+                let (d1, d2) = (p1 - p0, p2 - p0);
+                let (t21, t31) = (t1 - t0, t2 - t0);
+                let signed_area = t21.0[0] * t31.0[1] - t21.0[1] * t31.0[0];
+                let orientation = signed_area > zero;
+                let sign = if orientation { one } else { -one };
+                let tangent = (d1 * t31.0[1] - d2 * t21.0[1]) * sign;
+                FaceFrame {
+                    tangent: tangent.normal_or_zero(),
+                    orientation,
+                    contributes: signed_area != zero && d1.cross(d2) != Vect::ZERO,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut edge_tris = self.edge_tris().into_iter().collect::<Vec<_>>();
+        edge_tris.sort_unstable_by_key(|(edge, _)| *edge);
+        resolve_orientations(&edge_tris, &mut frames);
+
+        let corner =
+            |t: usize, v: usize| 3 * t + self.tris[t].iter().position(|&i| i == v).unwrap_or(0);
+        let mut groups = UnionFind::new(3 * self.tris.len());
+        for ([a, b], tris) in edge_tris.iter() {
+            for (k, &t) in tris.iter().enumerate() {
+                for &u in &tris[k + 1..] {
+                    if frames[t].orientation == frames[u].orientation {
+                        groups.union(corner(t, *a), corner(u, *a));
+                        groups.union(corner(t, *b), corner(u, *b));
+                    }
+                }
+            }
+        }
+
+        let mut sums = vec![Vect::ZERO; 3 * self.tris.len()];
+        for (t, tri) in self.tris.iter().enumerate() {
+            if !frames[t].contributes {
+                continue;
+            }
+            for i in 0..3 {
+                let vert = self.verts[tri[i]];
+                let normal = vert.normal();
+                let project = |d: Vect<3, V::Scalar>| (d - normal * normal.dot(d)).normal_or_zero();
+                let e0 = project(self.verts[tri[(i + 1) % 3]].pos() - vert.pos());
+                let e1 = project(self.verts[tri[(i + 2) % 3]].pos() - vert.pos());
+                let root = groups.find(3 * t + i);
+                sums[root] = sums[root] + project(frames[t].tangent) * angle_between(e0, e1);
+            }
+        }
+
+        let mut verts = self.verts.to_vec();
+        let mut split = HashMap::new();
+        let mut claimed = vec![false; self.verts.len()];
+        let tris = self
+            .tris
+            .iter()
+            .enumerate()
+            .map(|(t, tri)| {
+                let mut tri = *tri;
+                for (i, v) in tri.iter_mut().enumerate() {
+                    let root = groups.find(3 * t + i);
+                    let sign = if frames[t].orientation { one } else { -one };
+                    let [x, y, z] = sums[root].normal_or_zero().0;
+                    let index = *split.entry(root).or_insert_with(|| {
+                        let mut vert = self.verts[*v];
+                        vert.set_tangent(Vect([x, y, z, sign]));
+                        if claimed[*v] {
+                            verts.push(vert);
+                            verts.len() - 1
+                        } else {
+                            claimed[*v] = true;
+                            verts[*v] = vert;
+                            *v
+                        }
+                    });
+                    *v = index;
+                }
+                tri
+            })
+            .collect();
+
+        Mesh {
+            verts: verts.into_boxed_slice(),
+            tris,
+        }
+    }
+}
+
+/// Gives faces without texture area the orientation of a neighbour, so that
+/// they join its group rather than splitting it.
+fn resolve_orientations<S>(edge_tris: &[([usize; 2], Vec<usize>)], frames: &mut [FaceFrame<S>]) {
+    let mut resolved = frames.iter().map(|f| f.contributes).collect::<Vec<_>>();
+    loop {
+        let mut changed = false;
+        for (_, tris) in edge_tris {
+            let Some(&from) = tris.iter().find(|&&t| resolved[t]) else {
+                continue;
+            };
+            for &t in tris {
+                if !resolved[t] {
+                    frames[t].orientation = frames[from].orientation;
+                    resolved[t] = true;
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ear_algae::Vect;
+
+    use crate::{
+        vertex::{VertexData, VertexNormal, VertexPosition, VertexTangent, VertexUv},
+        Mesh,
+    };
+
+    #[derive(Copy, Clone, Debug, VertexData, VertexPosition, VertexNormal)]
+    struct Vert {
+        #[vertex(position)]
+        pos: Vect<3, f64>,
+        #[vertex(normal)]
+        normal: Vect<3, f64>,
+        uv: Vect<2, f64>,
+        tangent: Vect<4, f64>,
+    }
+
+    impl VertexUv for Vert {
+        fn uv(self) -> Vect<2, f64> {
+            self.uv
+        }
+
+        fn set_uv(&mut self, uv: Vect<2, f64>) {
+            self.uv = uv
+        }
+    }
+
+    impl VertexTangent for Vert {
+        fn tangent(self) -> Vect<4, f64> {
+            self.tangent
+        }
+
+        fn set_tangent(&mut self, tangent: Vect<4, f64>) {
+            self.tangent = tangent
+        }
+    }
+
+    /// A vertex in the `z = 0` plane facing up.
+    fn vert(x: f64, y: f64, u: f64, v: f64) -> Vert {
+        Vert {
+            pos: Vect([x, y, 0.]),
+            normal: Vect([0., 0., 1.]),
+            uv: Vect([u, v]),
+            tangent: Vect([0.; 4]),
+        }
+    }
+
+    fn assert_tangent(vert: Vert, expected: [f64; 4]) {
+        let got = vert.tangent.0;
+        let close = (0..4).all(|i| (got[i] - expected[i]).abs() < 1e-12);
+        assert!(close, "{got:?} != {expected:?}");
+    }
+
+    #[test]
+    fn quad_tangents_follow_u() {
+        let mesh = Mesh {
+            verts: [
+                vert(0., 0., 0., 0.),
+                vert(1., 0., 1., 0.),
+                vert(1., 1., 1., 1.),
+                vert(0., 1., 0., 1.),
+            ]
+            .into(),
+            tris: [[0, 1, 2], [0, 2, 3]].into(),
+        }
+        .generate_tangents();
+
+        assert_eq!(mesh.verts().len(), 4);
+        for &vert in mesh.verts() {
+            assert_tangent(vert, [1., 0., 0., 1.]);
+        }
+    }
+
+    /// Two quads sharing the edge `x = 1`, with the right one's texture
+    /// mirrored across it, as when one half of a symmetric model is unwrapped
+    /// over the other.
+    #[test]
+    fn mirrored_uvs_split_the_seam() {
+        let mesh = Mesh {
+            verts: [
+                vert(0., 0., 0., 0.),
+                vert(1., 0., 1., 0.),
+                vert(2., 0., 0., 0.),
+                vert(0., 1., 0., 1.),
+                vert(1., 1., 1., 1.),
+                vert(2., 1., 0., 1.),
+            ]
+            .into(),
+            tris: [[0, 1, 4], [0, 4, 3], [1, 2, 5], [1, 5, 4]].into(),
+        }
+        .generate_tangents();
+
+        // MikkTSpace points the mirrored side's tangent along -x and flips
+        // its bitangent sign, so that the bitangent still follows v.
+        assert_eq!(mesh.verts().len(), 8);
+        let tris = mesh.tris_iter().copied().collect::<Vec<_>>();
+        for &i in tris[..2].iter().flatten() {
+            assert_tangent(mesh.verts()[i], [1., 0., 0., 1.]);
+        }
+        for &i in tris[2..].iter().flatten() {
+            assert_tangent(mesh.verts()[i], [-1., 0., 0., -1.]);
+        }
+        assert_ne!(tris[0][1], tris[2][0]);
+        assert_eq!(mesh.verts()[tris[0][1]].pos, mesh.verts()[tris[2][0]].pos);
+    }
+}
@@ -0,0 +1,222 @@
+use std::cmp::Ordering;
+
+use ear_algae::{
+    prelude::*,
+    traits::{Field, Ring},
+};
+
+type Point<S> = [S; 2];
+
+/// Twice the signed area of `o`, `a`, `b`; positive if they turn
+/// counterclockwise.
+fn cross<S: Ring>(o: Point<S>, a: Point<S>, b: Point<S>) -> S {
+    (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+}
+
+fn signed_area<S: Ring>(pts: &[Point<S>]) -> S {
+    (0..pts.len()).fold(S::ZERO, |acc, i| {
+        let (a, b) = (pts[i], pts[(i + 1) % pts.len()]);
+        acc + a[0] * b[1] - a[1] * b[0]
+    })
+}
+
+/// Whether `p` lies inside or on the triangle `a`, `b`, `c` of either winding.
+fn in_triangle<S: Ring + PartialOrd>(p: Point<S>, a: Point<S>, b: Point<S>, c: Point<S>) -> bool {
+    let d = [cross(a, b, p), cross(b, c, p), cross(c, a, p)];
+    !(d.iter().any(|&d| d < S::ZERO) && d.iter().any(|&d| d > S::ZERO))
+}
+
+fn by_x<S: PartialOrd>(a: &Point<S>, b: &Point<S>) -> Ordering {
+    a[0].partial_cmp(&b[0]).unwrap_or(Ordering::Equal)
+}
+
+/// The normal of a possibly non-planar polygon by Newell's method, with a
+/// length of twice its area.
+pub(crate) fn polygon_normal<S: Ring>(pts: &[Vect<3, S>]) -> Vect<3, S> {
+    (0..pts.len()).fold(Vect::ZERO, |acc, i| {
+        acc + pts[i].cross(pts[(i + 1) % pts.len()])
+    })
+}
+
+/// Two unit axes spanning the plane with this normal, such that polygons
+/// winding counterclockwise about the normal also do in the plane.
+fn plane_axes<S: Field + PartialOrd>(normal: Vect<3, S>) -> Option<[Vect<3, S>; 2]> {
+    let n = normal.normal_or_zero();
+    if n == Vect::ZERO {
+        return None;
+    }
+    let abs = n.0.map(|x| if x < S::ZERO { -x } else { x });
+    let axis = (0..3)
+        .min_by(|&a, &b| abs[a].partial_cmp(&abs[b]).unwrap_or(Ordering::Equal))
+        .unwrap_or(0);
+    let mut e = [S::ZERO; 3];
+    e[axis] = S::ONE;
+    let u = Vect(e).cross(n).normal_or_zero();
+    Some([u, n.cross(u)])
+}
+
+/// Triangulates a planar polygon, which may be concave and may have holes, by
+/// ear clipping in its best-fit plane. Triangles wind the same way as `outer`.
+/// Holes may wind either way.
+pub(crate) fn triangulate<T: Copy, S: Field + PartialOrd>(
+    outer: &[T],
+    holes: &[&[T]],
+    pos: impl Fn(T) -> Vect<3, S>,
+) -> Vec<[T; 3]> {
+    if outer.len() < 3 {
+        return Vec::new();
+    }
+    let outer_pos = outer.iter().map(|&v| pos(v)).collect::<Vec<_>>();
+    let Some([u, v]) = plane_axes(polygon_normal(&outer_pos)) else {
+        // Nothing to clip against; a fan at least covers the outline.
+        return (1..outer.len() - 1)
+            .map(|i| [outer[0], outer[i], outer[i + 1]])
+            .collect();
+    };
+    let project = |p: Vect<3, S>| [p.dot(u), p.dot(v)];
+
+    let mut verts = outer.to_vec();
+    let mut pts = outer_pos.into_iter().map(project).collect::<Vec<_>>();
+    let mut ring = (0..outer.len()).collect::<Vec<_>>();
+
+    let mut hole_rings = Vec::new();
+    for hole in holes.iter().filter(|h| h.len() >= 3) {
+        let start = verts.len();
+        verts.extend_from_slice(hole);
+        pts.extend(hole.iter().map(|&v| project(pos(v))));
+        let mut hole_ring = (start..verts.len()).collect::<Vec<_>>();
+        if signed_area(&pts[start..]) > S::ZERO {
+            hole_ring.reverse();
+        }
+        hole_rings.push(hole_ring);
+    }
+
+    // Bridging the rightmost hole first keeps later bridges from crossing it.
+    let rightmost = |hole: &Vec<usize>| hole.iter().map(|&i| pts[i]).max_by(by_x).map(|p| p[0]);
+    hole_rings.sort_by(|a, b| {
+        rightmost(b)
+            .partial_cmp(&rightmost(a))
+            .unwrap_or(Ordering::Equal)
+    });
+    for hole in &hole_rings {
+        bridge(&mut ring, hole, &pts);
+    }
+
+    ear_clip(ring, &pts)
+        .into_iter()
+        .map(|tri| tri.map(|i| verts[i]))
+        .collect()
+}
+
+/// Splices `hole` into `ring` along a bridge from its rightmost vertex to a
+/// vertex of the ring that it can see, following Eberly's method.
+fn bridge<S: Field + PartialOrd>(ring: &mut Vec<usize>, hole: &[usize], pts: &[Point<S>]) {
+    let Some(start) = (0..hole.len()).max_by(|&a, &b| by_x(&pts[hole[a]], &pts[hole[b]])) else {
+        return;
+    };
+    let m = pts[hole[start]];
+    let len = ring.len();
+
+    // The nearest crossing of a ray from `m` towards +x, and the ring vertex
+    // at the right end of the edge it crosses.
+    let mut hit: Option<(S, usize)> = None;
+    for i in 0..len {
+        let (a, b) = (pts[ring[i]], pts[ring[(i + 1) % len]]);
+        let straddles = (a[1] <= m[1] && m[1] <= b[1]) || (b[1] <= m[1] && m[1] <= a[1]);
+        if !straddles || a[1] == b[1] {
+            continue;
+        }
+        let x = a[0] + (b[0] - a[0]) * ((m[1] - a[1]) / (b[1] - a[1]));
+        if x < m[0] || hit.is_some_and(|(best, _)| best <= x) {
+            continue;
+        }
+        hit = Some((x, if a[0] > b[0] { i } else { (i + 1) % len }));
+    }
+
+    let p = match hit {
+        Some((x, p)) => {
+            // A vertex inside the triangle between `m`, the crossing and `p`
+            // could block the bridge; the one closest in angle to the ray
+            // can't be blocked.
+            let crossing = [x, m[1]];
+            let steepness = |q: Point<S>| {
+                let dy = q[1] - m[1];
+                (if dy < S::ZERO { -dy } else { dy }, q[0] - m[0])
+            };
+            (0..len)
+                .filter(|&j| {
+                    let q = pts[ring[j]];
+                    j != p && q[0] > m[0] && in_triangle(q, m, crossing, pts[ring[p]])
+                })
+                .fold(p, |best, j| {
+                    let (dy_j, dx_j) = steepness(pts[ring[j]]);
+                    let (dy_b, dx_b) = steepness(pts[ring[best]]);
+                    if dy_j * dx_b < dy_b * dx_j {
+                        j
+                    } else {
+                        best
+                    }
+                })
+        }
+        None => (0..len)
+            .min_by(|&a, &b| {
+                let d = |j: usize| {
+                    let q = pts[ring[j]];
+                    (q[0] - m[0]) * (q[0] - m[0]) + (q[1] - m[1]) * (q[1] - m[1])
+                };
+                d(a).partial_cmp(&d(b)).unwrap_or(Ordering::Equal)
+            })
+            .unwrap_or(0),
+    };
+
+    // Earlier bridges visit their ring vertex more than once; only one of
+    // those visits faces `m`.
+    let faces_m = |j: usize| {
+        let [a, b, c] = [(j + len - 1) % len, j, (j + 1) % len].map(|k| pts[ring[k]]);
+        let (after_a, before_c) = (cross(a, b, m) >= S::ZERO, cross(b, c, m) >= S::ZERO);
+        if cross(a, b, c) >= S::ZERO {
+            after_a && before_c
+        } else {
+            after_a || before_c
+        }
+    };
+    let p = (0..len)
+        .find(|&j| pts[ring[j]] == pts[ring[p]] && faces_m(j))
+        .unwrap_or(p);
+
+    let mut spliced = ring[..=p].to_vec();
+    spliced.extend(hole[start..].iter().chain(&hole[..=start]));
+    spliced.push(ring[p]);
+    spliced.extend_from_slice(&ring[p + 1..]);
+    *ring = spliced;
+}
+
+/// Clips ears off a counterclockwise ring until one triangle is left. If the
+/// ring is too degenerate to have an ear, a vertex is clipped anyway so that
+/// the whole outline is still covered.
+fn ear_clip<S: Ring + PartialOrd>(mut ring: Vec<usize>, pts: &[Point<S>]) -> Vec<[usize; 3]> {
+    let mut tris = Vec::new();
+    let mut i = 0;
+    let mut stalled = 0;
+    while ring.len() > 3 {
+        let n = ring.len();
+        let tri = [ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]];
+        let [a, b, c] = tri.map(|j| pts[j]);
+        let is_ear = cross(a, b, c) > S::ZERO
+            && !ring.iter().any(|&j| {
+                let q = pts[j];
+                q != a && q != b && q != c && in_triangle(q, a, b, c)
+            });
+        if is_ear || stalled >= n {
+            tris.push(tri);
+            ring.remove(i);
+            i = (i + n - 2) % (n - 1);
+            stalled = 0;
+        } else {
+            i = (i + 1) % n;
+            stalled += 1;
+        }
+    }
+    tris.push([ring[0], ring[1], ring[2]]);
+    tris
+}
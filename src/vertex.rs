@@ -1,4 +1,9 @@
-use ear_algae::{traits::Field, Vect};
+use ear_algae::{prelude::*, traits::Field};
+use serde::{Deserialize, Serialize};
+
+use crate::ply::PlyVertex;
+
+pub use mish_mesh_derive::{VertexData, VertexNormal, VertexPosition};
 
 // pub trait VertexMerge: VertexData {
 //     fn close_to(self, other: Self, threshold: Self::Scalar) -> bool;
@@ -29,6 +34,70 @@ pub trait VertexNormal: VertexData {
     fn set_normal(&mut self, normal: Vect<3, Self::Scalar>);
 }
 
+pub trait VertexUv: VertexData {
+    fn uv(self) -> Vect<2, Self::Scalar>;
+    fn set_uv(&mut self, uv: Vect<2, Self::Scalar>);
+}
+
+pub trait VertexTangent: VertexData {
+    /// The tangent in `xyz` and the bitangent sign in `w`.
+    fn tangent(self) -> Vect<4, Self::Scalar>;
+    fn set_tangent(&mut self, tangent: Vect<4, Self::Scalar>);
+}
+
+/// A colour channel: `u8` for 0 to 255, or a float for 0 to 1.
+pub trait ColorChannel: Copy {
+    /// Full intensity, used as the alpha of colours that don't carry one.
+    const MAX: Self;
+    /// The channel as a fraction of [`Self::MAX`].
+    fn to_unit(self) -> f64;
+    /// The channel nearest to `unit` times [`Self::MAX`].
+    fn from_unit(unit: f64) -> Self;
+}
+
+impl ColorChannel for u8 {
+    const MAX: Self = u8::MAX;
+
+    fn to_unit(self) -> f64 {
+        f64::from(self) / 255.
+    }
+
+    fn from_unit(unit: f64) -> Self {
+        (unit * 255.).round().clamp(0., 255.) as u8
+    }
+}
+
+impl ColorChannel for f32 {
+    const MAX: Self = 1.;
+
+    fn to_unit(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_unit(unit: f64) -> Self {
+        unit as f32
+    }
+}
+
+impl ColorChannel for f64 {
+    const MAX: Self = 1.;
+
+    fn to_unit(self) -> f64 {
+        self
+    }
+
+    fn from_unit(unit: f64) -> Self {
+        unit
+    }
+}
+
+pub trait VertexColor: VertexData {
+    type Channel: ColorChannel;
+    /// Red, green, blue and alpha.
+    fn color(self) -> [Self::Channel; 4];
+    fn set_color(&mut self, color: [Self::Channel; 4]);
+}
+
 impl<S: Field> VertexData for Vect<3, S> {
     type Scalar = S;
 }
@@ -42,3 +111,136 @@ impl<S: Field> VertexPosition for Vect<3, S> {
         *self = pos
     }
 }
+
+pub trait VertexBlend: VertexData {
+    /// Combines vertices with weights that sum to one.
+    fn blend(weighted: &[(Self, Self::Scalar)]) -> Self;
+}
+
+impl<S: Field> VertexBlend for Vect<3, S> {
+    fn blend(weighted: &[(Self, Self::Scalar)]) -> Self {
+        blend_field(weighted, |v| v)
+    }
+}
+
+fn blend_field<V: Copy, S: Field>(
+    weighted: &[(V, S)],
+    field: impl Fn(V) -> Vect<3, S>,
+) -> Vect<3, S> {
+    weighted.iter().fold(Vect::ZERO, |acc, &(v, w)| acc + field(v) * w)
+}
+
+/// Blends colours channel by channel, rounding to the nearest `u8`.
+fn blend_color<V: Copy, S: Field + Into<f64>, C: ColorChannel>(
+    weighted: &[(V, S)],
+    color: impl Fn(V) -> [C; 4],
+) -> [C; 4] {
+    let mut sum = [0.; 4];
+    for &(v, w) in weighted {
+        let w = w.into();
+        for (sum, channel) in sum.iter_mut().zip(color(v)) {
+            *sum += channel.to_unit() * w;
+        }
+    }
+    sum.map(C::from_unit)
+}
+
+/// A vertex with a position and a normal.
+#[derive(
+    Copy, Clone, PartialEq, Debug, Deserialize, Serialize,
+    VertexData, VertexPosition, VertexNormal, PlyVertex,
+)]
+pub struct PosNormal<S> {
+    #[vertex(position)]
+    pub pos: Vect<3, S>,
+    #[vertex(normal)]
+    pub normal: Vect<3, S>,
+}
+
+/// A vertex with a position and a colour.
+#[derive(
+    Copy, Clone, PartialEq, Debug, Deserialize, Serialize,
+    VertexData, VertexPosition,
+)]
+pub struct PosColor<S, C> {
+    #[vertex(position)]
+    pub pos: Vect<3, S>,
+    pub color: [C; 4],
+}
+
+/// A vertex with a position, a normal and a colour.
+#[derive(
+    Copy, Clone, PartialEq, Debug, Deserialize, Serialize,
+    VertexData, VertexPosition, VertexNormal,
+)]
+pub struct PosNormalColor<S, C> {
+    #[vertex(position)]
+    pub pos: Vect<3, S>,
+    #[vertex(normal)]
+    pub normal: Vect<3, S>,
+    pub color: [C; 4],
+}
+
+/// Blends the normals like positions, then renormalizes them.
+impl<S: Field> VertexBlend for PosNormal<S> {
+    fn blend(weighted: &[(Self, S)]) -> Self {
+        Self {
+            pos: blend_field(weighted, |v| v.pos),
+            normal: blend_field(weighted, |v| v.normal).normal_or_zero(),
+        }
+    }
+}
+
+/// Colours need a scalar that converts to `f64`, which `f32` and `f64` both do.
+impl<S: Field + Into<f64>, C: ColorChannel> VertexBlend for PosColor<S, C> {
+    fn blend(weighted: &[(Self, S)]) -> Self {
+        Self {
+            pos: blend_field(weighted, |v| v.pos),
+            color: blend_color(weighted, |v| v.color),
+        }
+    }
+}
+
+impl<S: Field + Into<f64>, C: ColorChannel> VertexBlend for PosNormalColor<S, C> {
+    fn blend(weighted: &[(Self, S)]) -> Self {
+        Self {
+            pos: blend_field(weighted, |v| v.pos),
+            normal: blend_field(weighted, |v| v.normal).normal_or_zero(),
+            color: blend_color(weighted, |v| v.color),
+        }
+    }
+}
+
+impl<S: Field> From<Vect<3, S>> for PosNormal<S> {
+    /// Leaves the normal zero, for `Mesh::autocomplete_normals` to fill in.
+    fn from(pos: Vect<3, S>) -> Self {
+        Self {
+            pos,
+            normal: Vect::ZERO,
+        }
+    }
+}
+
+impl<S: Field, C: ColorChannel> VertexColor for PosColor<S, C> {
+    type Channel = C;
+
+    fn color(self) -> [C; 4] {
+        self.color
+    }
+
+    fn set_color(&mut self, color: [C; 4]) {
+        self.color = color
+    }
+}
+
+impl<S: Field, C: ColorChannel> VertexColor for PosNormalColor<S, C> {
+    type Channel = C;
+
+    fn color(self) -> [C; 4] {
+        self.color
+    }
+
+    fn set_color(&mut self, color: [C; 4]) {
+        self.color = color
+    }
+}
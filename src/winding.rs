@@ -0,0 +1,183 @@
+use ear_algae::{
+    prelude::*,
+    traits::{Field, Ring},
+};
+
+use crate::{
+    bvh::{Bvh, NodeKind},
+    scalar::int,
+    vertex::VertexPosition,
+    Mesh,
+};
+
+/// The solid angle subtended by triangle `abc` as seen from `p`, signed by the
+/// triangle's winding.
+fn solid_angle<S: Field + PartialOrd>(p: Vect<3, S>, tri: [Vect<3, S>; 3]) -> S {
+    let [a, b, c] = tri.map(|v| v - p);
+    let (la, lb, lc) = (a.magn(), b.magn(), c.magn());
+    let num = a.dot(b.cross(c));
+    let den = la * lb * lc + a.dot(b) * lc + a.dot(c) * lb + b.dot(c) * la;
+
+    // 2 atan2(num, den), by way of acos.
+    let hyp = (num * num + den * den).sqrt();
+    if hyp == S::ZERO {
+        return S::ZERO;
+    }
+    let half = (den / hyp).acos();
+    if num < S::ZERO {
+        -(half + half)
+    } else {
+        half + half
+    }
+}
+
+fn full_sphere<S: Field>() -> S {
+    (-S::ONE).acos() * int(4)
+}
+
+impl<V: VertexPosition> Mesh<V>
+where
+    V::Scalar: PartialOrd,
+{
+    /// The generalized winding number of the mesh around `p`: about one inside a
+    /// closed outward-facing mesh, about zero outside, and in between near holes.
+    ///
+    /// This sums over every triangle; build a [`WindingNumberTree`] to query
+    /// many points.
+    pub fn winding_number(&self, p: Vect<3, V::Scalar>) -> V::Scalar {
+        self.tris_iter()
+            .map(|t| solid_angle(p, t.map(|i| self.verts[i].pos())))
+            .fold(V::Scalar::ZERO, |acc, w| acc + w)
+            / full_sphere()
+    }
+
+    pub fn contains(&self, p: Vect<3, V::Scalar>) -> bool {
+        let winding = self.winding_number(p);
+        winding + winding >= V::Scalar::ONE
+    }
+
+    pub fn winding_number_tree(&self) -> WindingNumberTree<V::Scalar> {
+        WindingNumberTree::new(self)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Dipole<S: Field> {
+    center: Vect<3, S>,
+    /// Sum of the area-weighted normals beneath the node.
+    normal: Vect<3, S>,
+    radius: S,
+}
+
+/// Hierarchical generalized winding numbers, which treat distant clusters of
+/// triangles as a single dipole.
+#[derive(Clone, Debug)]
+pub struct WindingNumberTree<S: Field> {
+    bvh: Bvh<S>,
+    dipoles: Vec<Dipole<S>>,
+    accuracy: S,
+}
+
+impl<S: Field + PartialOrd> WindingNumberTree<S> {
+    pub fn new<V: VertexPosition<Scalar = S>>(mesh: &Mesh<V>) -> Self {
+        let bvh = mesh.bvh();
+        let zero = Dipole {
+            center: Vect::ZERO,
+            normal: Vect::ZERO,
+            radius: S::ZERO,
+        };
+        let mut dipoles = vec![zero; bvh.nodes.len()];
+        let mut areas = vec![S::ZERO; bvh.nodes.len()];
+
+        // Children always come after their parent, so walk the nodes backwards.
+        for n in (0..bvh.nodes.len()).rev() {
+            let (area, center, normal) = match bvh.nodes[n].kind {
+                NodeKind::Leaf { start, end } => bvh.order[start..end].iter().fold(
+                    (S::ZERO, Vect::ZERO, Vect::ZERO),
+                    |(area, center, normal), &t| {
+                        let [a, b, c] = bvh.tris[t];
+                        let cross = (b - a).cross(c - a);
+                        let tri_area = cross.magn();
+                        (
+                            area + tri_area,
+                            center + (a + b + c) * (tri_area / int(3)),
+                            normal + cross * (S::ONE / int(2)),
+                        )
+                    },
+                ),
+                NodeKind::Branch { left, right } => (
+                    areas[left] + areas[right],
+                    dipoles[left].center * areas[left] + dipoles[right].center * areas[right],
+                    dipoles[left].normal + dipoles[right].normal,
+                ),
+            };
+            let bounds = bvh.nodes[n].bounds;
+            let center = if area == S::ZERO {
+                bounds.center()
+            } else {
+                center * (S::ONE / area)
+            };
+            let radius = (0..3)
+                .map(|i| {
+                    let lo = center[i] - bounds.min[i];
+                    let hi = bounds.max[i] - center[i];
+                    if lo > hi {
+                        lo * lo
+                    } else {
+                        hi * hi
+                    }
+                })
+                .fold(S::ZERO, |acc, d| acc + d)
+                .sqrt();
+            areas[n] = area;
+            dipoles[n] = Dipole {
+                center,
+                normal,
+                radius,
+            };
+        }
+
+        Self {
+            bvh,
+            dipoles,
+            accuracy: int(2),
+        }
+    }
+
+    /// Sets how many node radii away a point must be before the node is
+    /// approximated. Larger is slower and more accurate; the default is 2.
+    pub fn with_accuracy(self, accuracy: S) -> Self {
+        Self { accuracy, ..self }
+    }
+
+    pub fn winding_number(&self, p: Vect<3, S>) -> S {
+        let mut total = S::ZERO;
+        let mut stack = Vec::new();
+        if !self.bvh.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(n) = stack.pop() {
+            let dipole = &self.dipoles[n];
+            let offset = dipole.center - p;
+            let distance = offset.magn();
+            if distance > dipole.radius * self.accuracy {
+                total = total + dipole.normal.dot(offset) / (distance * distance * distance);
+                continue;
+            }
+            match self.bvh.nodes[n].kind {
+                NodeKind::Leaf { start, end } => {
+                    for &t in &self.bvh.order[start..end] {
+                        total = total + solid_angle(p, self.bvh.tris[t]);
+                    }
+                }
+                NodeKind::Branch { left, right } => stack.extend([left, right]),
+            }
+        }
+        total / full_sphere()
+    }
+
+    pub fn contains(&self, p: Vect<3, S>) -> bool {
+        let winding = self.winding_number(p);
+        winding + winding >= S::ONE
+    }
+}